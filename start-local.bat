@@ -34,7 +34,7 @@ if errorlevel 1 (
 
 REM 4. Start local miner (background)
 echo Starting local miner...
-start /b minerd.exe -l
+start /b xrs-miner.exe
 
 REM 5. Example TX submit
 echo Submitting example TX...
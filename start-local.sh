@@ -16,7 +16,7 @@ Run your **private XerisCoin chain locally**—test the patent-pending triple co
 1. Unzip & `make all` (builds binaries).
 2. `./start-local.sh` (gens keys, starts node/miner, claims airdrop).
 3. Interact:
-   - Mine: `./minerd -l` (logs MH/s, submits local blocks).
+   - Mine: `./xrs-miner` (logs H/s, solves the node's leader slots over /work).
    - TX: `./submit_tx.sh`.
    - Explore: `curl http://127.0.0.1:8081/balances`.
 4. Stop: Ctrl+C.
@@ -0,0 +1,48 @@
+// Patent Pending Copyright © 2025 Xeris Web Co. All rights reserved.
+// XerisCoin Account Model - Lamports, Owner Program, Data, Executable (Local Alpha)
+// Rent-exempt minimum balances keep every stored account paid for
+
+use solana_sdk::pubkey::Pubkey;
+#[allow(deprecated)]
+use solana_sdk::system_program;
+use serde::{Serialize, Deserialize};
+
+/// Bytes charged for every account on top of its data (pubkey, owner, lamports, flags).
+pub const ACCOUNT_STORAGE_OVERHEAD: u64 = 128;
+pub const LAMPORTS_PER_BYTE_YEAR: u64 = 3_480;
+/// Years of rent an account must hold to be exempt; accounts below that are rejected.
+pub const EXEMPTION_THRESHOLD_YEARS: u64 = 2;
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Account {
+    pub lamports: u64,
+    pub owner: Pubkey,
+    pub data: Vec<u8>,
+    pub executable: bool,
+}
+
+impl Account {
+    /// A plain wallet account: owned by the system program, no data.
+    pub fn new_system(lamports: u64) -> Self {
+        Account {
+            lamports,
+            owner: system_program::id(),
+            data: Vec::new(),
+            executable: false,
+        }
+    }
+
+    /// Zero lamports and no data: the account does not exist and is dropped from state.
+    pub fn is_empty(&self) -> bool {
+        self.lamports == 0 && self.data.is_empty()
+    }
+
+    pub fn is_rent_exempt(&self) -> bool {
+        self.lamports >= minimum_balance(self.data.len())
+    }
+}
+
+/// Lamports an account holding `data_len` bytes needs to be rent exempt.
+pub fn minimum_balance(data_len: usize) -> u64 {
+    (ACCOUNT_STORAGE_OVERHEAD + data_len as u64) * LAMPORTS_PER_BYTE_YEAR * EXEMPTION_THRESHOLD_YEARS
+}
@@ -0,0 +1,494 @@
+// Patent Pending Copyright © 2025 Xeris Web Co. All rights reserved.
+// XerisCoin HTTP API Types - Typed Responses, Error Codes & OpenAPI (Local Alpha)
+// Shared by network.rs endpoints on 127.0.0.1:4001
+
+use std::convert::Infallible;
+use serde::Serialize;
+use serde_json::{json, Value};
+use warp::http::StatusCode;
+use warp::reply::{Json, WithStatus};
+use warp::{Rejection, Reply};
+use crate::error::{ConsensusError, LedgerError, MempoolError};
+
+/// Stable, machine-readable error codes returned in `error.code`.
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ErrorCode {
+    InvalidRequest,
+    InvalidEncoding,
+    InvalidSignature,
+    InvalidPubkey,
+    InvalidInstruction,
+    DuplicateSignature,
+    BlockhashNotFound,
+    InsufficientFunds,
+    InsufficientFundsForRent,
+    UnknownSender,
+    AirdropLimitExceeded,
+    MaliciousBlock,
+    StaleSlot,
+    ValidatorJailed,
+    InvalidWork,
+    MempoolFull,
+    SenderLimitExceeded,
+    NotFound,
+    MethodNotAllowed,
+    StorageFailure,
+    Internal,
+}
+
+impl ErrorCode {
+    pub const ALL: [ErrorCode; 21] = [
+        ErrorCode::InvalidRequest,
+        ErrorCode::InvalidEncoding,
+        ErrorCode::InvalidSignature,
+        ErrorCode::InvalidPubkey,
+        ErrorCode::InvalidInstruction,
+        ErrorCode::DuplicateSignature,
+        ErrorCode::BlockhashNotFound,
+        ErrorCode::InsufficientFunds,
+        ErrorCode::InsufficientFundsForRent,
+        ErrorCode::UnknownSender,
+        ErrorCode::AirdropLimitExceeded,
+        ErrorCode::MaliciousBlock,
+        ErrorCode::StaleSlot,
+        ErrorCode::ValidatorJailed,
+        ErrorCode::InvalidWork,
+        ErrorCode::MempoolFull,
+        ErrorCode::SenderLimitExceeded,
+        ErrorCode::NotFound,
+        ErrorCode::MethodNotAllowed,
+        ErrorCode::StorageFailure,
+        ErrorCode::Internal,
+    ];
+
+    pub fn status(self) -> StatusCode {
+        match self {
+            ErrorCode::InvalidRequest
+            | ErrorCode::InvalidEncoding
+            | ErrorCode::InvalidSignature
+            | ErrorCode::InvalidPubkey
+            | ErrorCode::InvalidInstruction
+            | ErrorCode::BlockhashNotFound => StatusCode::BAD_REQUEST,
+            ErrorCode::DuplicateSignature | ErrorCode::StaleSlot => StatusCode::CONFLICT,
+            ErrorCode::InsufficientFunds
+            | ErrorCode::InsufficientFundsForRent
+            | ErrorCode::MaliciousBlock
+            | ErrorCode::InvalidWork => StatusCode::UNPROCESSABLE_ENTITY,
+            ErrorCode::UnknownSender | ErrorCode::NotFound => StatusCode::NOT_FOUND,
+            ErrorCode::AirdropLimitExceeded | ErrorCode::ValidatorJailed => StatusCode::FORBIDDEN,
+            ErrorCode::MethodNotAllowed => StatusCode::METHOD_NOT_ALLOWED,
+            ErrorCode::SenderLimitExceeded => StatusCode::TOO_MANY_REQUESTS,
+            ErrorCode::StorageFailure | ErrorCode::MempoolFull => StatusCode::SERVICE_UNAVAILABLE,
+            ErrorCode::Internal => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+}
+
+impl From<&LedgerError> for ErrorCode {
+    fn from(e: &LedgerError) -> Self {
+        match e {
+            LedgerError::InsufficientFunds { .. } => ErrorCode::InsufficientFunds,
+            LedgerError::DuplicateSignature(_) => ErrorCode::DuplicateSignature,
+            LedgerError::BlockhashNotFound(_) => ErrorCode::BlockhashNotFound,
+            LedgerError::InvalidSignature(_) => ErrorCode::InvalidSignature,
+            LedgerError::UnknownSender(_) => ErrorCode::UnknownSender,
+            LedgerError::InvalidPubkey(_) => ErrorCode::InvalidPubkey,
+            LedgerError::InvalidInstruction(_) => ErrorCode::InvalidInstruction,
+            LedgerError::InsufficientFundsForRent { .. } => ErrorCode::InsufficientFundsForRent,
+            LedgerError::AirdropLimitExceeded { .. } => ErrorCode::AirdropLimitExceeded,
+            LedgerError::MaliciousBlock { .. } => ErrorCode::MaliciousBlock,
+            LedgerError::StaleSlot { .. } => ErrorCode::StaleSlot,
+            LedgerError::ValidatorJailed { .. } => ErrorCode::ValidatorJailed,
+            LedgerError::SlotNotFound(_) | LedgerError::SignatureNotFound(_) | LedgerError::FaucetUnavailable => {
+                ErrorCode::NotFound
+            }
+            LedgerError::Io(_) | LedgerError::Storage(_) => ErrorCode::StorageFailure,
+        }
+    }
+}
+
+impl From<&ConsensusError> for ErrorCode {
+    fn from(e: &ConsensusError) -> Self {
+        match e {
+            ConsensusError::Ledger(e) => ErrorCode::from(e),
+            ConsensusError::StaleWork { .. } => ErrorCode::StaleSlot,
+            ConsensusError::InvalidWork { .. } => ErrorCode::InvalidWork,
+            ConsensusError::InsufficientStake { .. } | ConsensusError::InsufficientVotes { .. } | ConsensusError::Pow(_) => {
+                ErrorCode::Internal
+            }
+        }
+    }
+}
+
+impl From<&MempoolError> for ErrorCode {
+    fn from(e: &MempoolError) -> Self {
+        match e {
+            MempoolError::MissingSignature => ErrorCode::InvalidSignature,
+            MempoolError::Duplicate(_) => ErrorCode::DuplicateSignature,
+            MempoolError::Full { .. } => ErrorCode::MempoolFull,
+            MempoolError::SenderLimit { .. } => ErrorCode::SenderLimitExceeded,
+        }
+    }
+}
+
+#[derive(Serialize, Debug)]
+pub struct ApiError {
+    pub code: ErrorCode,
+    pub message: String,
+}
+
+#[derive(Serialize, Debug)]
+pub struct ApiResponse {
+    pub ok: bool,
+    pub signature: Option<String>,
+    pub slot: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub data: Option<Value>,
+    pub error: Option<ApiError>,
+}
+
+impl ApiResponse {
+    pub fn success(signature: Option<String>, slot: Option<u64>) -> Self {
+        ApiResponse { ok: true, signature, slot, data: None, error: None }
+    }
+
+    pub fn failure(code: ErrorCode, message: impl Into<String>) -> Self {
+        ApiResponse {
+            ok: false,
+            signature: None,
+            slot: None,
+            data: None,
+            error: Some(ApiError { code, message: message.into() }),
+        }
+    }
+
+    pub fn from_ledger_error(e: &LedgerError) -> Self {
+        ApiResponse::failure(ErrorCode::from(e), e.to_string())
+    }
+
+    pub fn from_consensus_error(e: &ConsensusError) -> Self {
+        ApiResponse::failure(ErrorCode::from(e), e.to_string())
+    }
+
+    pub fn with_data(mut self, data: Value) -> Self {
+        self.data = Some(data);
+        self
+    }
+
+    pub fn with_signature(mut self, signature: String) -> Self {
+        self.signature = Some(signature);
+        self
+    }
+
+    pub fn into_reply(self) -> WithStatus<Json> {
+        let status = self.error.as_ref().map(|e| e.code.status()).unwrap_or(StatusCode::OK);
+        warp::reply::with_status(warp::reply::json(&self), status)
+    }
+}
+
+/// Turns warp rejections (bad JSON body, unknown path, wrong method) into typed JSON errors.
+pub async fn handle_rejection(rejection: Rejection) -> Result<impl Reply, Infallible> {
+    let response = if rejection.is_not_found() {
+        ApiResponse::failure(ErrorCode::NotFound, "Endpoint not found")
+    } else if let Some(e) = rejection.find::<warp::filters::body::BodyDeserializeError>() {
+        ApiResponse::failure(ErrorCode::InvalidRequest, format!("Invalid request body: {}", e))
+    } else if rejection.find::<warp::reject::MethodNotAllowed>().is_some() {
+        ApiResponse::failure(ErrorCode::MethodNotAllowed, "Method not allowed")
+    } else {
+        ApiResponse::failure(ErrorCode::Internal, format!("Unhandled rejection: {:?}", rejection))
+    };
+    Ok(response.into_reply())
+}
+
+/// OpenAPI 3 description of the node HTTP API, served at /openapi.json.
+pub fn openapi_spec() -> Value {
+    let mut snapshot_responses = response_set(&["404", "500"]);
+    snapshot_responses["200"] = json!({
+        "description": "Snapshot bytes",
+        "content": { "application/octet-stream": { "schema": { "type": "string", "format": "binary" } } }
+    });
+    json!({
+        "openapi": "3.0.3",
+        "info": {
+            "title": "XerisCoin Local Alpha Node API",
+            "version": env!("CARGO_PKG_VERSION"),
+        },
+        "servers": [{ "url": "http://127.0.0.1:4001" }],
+        "paths": {
+            "/airdrop/{address}/{amount}": {
+                "get": {
+                    "summary": "Airdrop lamports from the treasury to an address",
+                    "parameters": [
+                        { "name": "address", "in": "path", "required": true, "schema": { "type": "string" } },
+                        { "name": "amount", "in": "path", "required": true, "schema": { "type": "integer", "format": "uint64" } }
+                    ],
+                    "responses": response_set(&["200", "400", "403", "500", "503"]),
+                }
+            },
+            "/submit_transaction": {
+                "post": {
+                    "summary": "Validate a base64-encoded bincode transaction and queue it in the mempool for the next block",
+                    "requestBody": {
+                        "required": true,
+                        "content": { "application/json": { "schema": { "$ref": "#/components/schemas/SubmitTransactionRequest" } } }
+                    },
+                    "responses": response_set(&["200", "400", "404", "409", "422", "429", "500", "503"]),
+                }
+            },
+            "/latest_blockhash": {
+                "get": {
+                    "summary": "Latest blockhash to sign transactions against; expires after max_age_blocks blocks",
+                    "responses": response_set(&["200", "500"]),
+                }
+            },
+            "/account/{address}": {
+                "get": {
+                    "summary": "getAccountInfo: lamports, owner program, base64 data, executable flag and rent-exempt minimum",
+                    "parameters": [
+                        { "name": "address", "in": "path", "required": true, "schema": { "type": "string" } }
+                    ],
+                    "responses": response_set(&["200", "400", "404", "500"]),
+                }
+            },
+            "/account_proof/{address}/{slot}": {
+                "get": {
+                    "summary": "Sparse Merkle proof of an account (or its absence) against the state root of the block at or before slot",
+                    "parameters": [
+                        { "name": "address", "in": "path", "required": true, "schema": { "type": "string" } },
+                        { "name": "slot", "in": "path", "required": true, "schema": { "type": "integer", "format": "uint64" } }
+                    ],
+                    "responses": response_set(&["200", "400", "404", "500"]),
+                }
+            },
+            "/tx_proof/{signature}": {
+                "get": {
+                    "summary": "Inclusion receipt: block slot, leaf index and Merkle proof against that block's tx_root",
+                    "parameters": [
+                        { "name": "signature", "in": "path", "required": true, "schema": { "type": "string" } }
+                    ],
+                    "responses": response_set(&["200", "400", "404", "500"]),
+                }
+            },
+            "/block/{slot}": {
+                "get": {
+                    "summary": "Block at slot: from memory, or from disk in archival mode; NOT_FOUND once pruned",
+                    "parameters": [
+                        { "name": "slot", "in": "path", "required": true, "schema": { "type": "integer", "format": "uint64" } }
+                    ],
+                    "responses": response_set(&["200", "404", "500"]),
+                }
+            },
+            "/stake/{owner}": {
+                "get": {
+                    "summary": "Owner's stake account: delegated validator, active stake, stake activating or deactivating at the next epoch, unbonding lamports and their unlock epoch, last change slot and the delegated validator's reputation",
+                    "parameters": [
+                        { "name": "owner", "in": "path", "required": true, "schema": { "type": "string" } }
+                    ],
+                    "responses": response_set(&["200", "400", "404", "500"]),
+                }
+            },
+            "/validator/{identity}": {
+                "get": {
+                    "summary": "Validator's registration, active-set membership, commission, rewards pending until the next epoch boundary, delegated and effective (capped) stake and delegator count, reputation from missed and produced leader slots, leader weight and slashing record",
+                    "parameters": [
+                        { "name": "identity", "in": "path", "required": true, "schema": { "type": "string" } }
+                    ],
+                    "responses": response_set(&["200", "400", "404", "500"]),
+                }
+            },
+            "/rewards": {
+                "get": {
+                    "summary": "Per-epoch staking rewards, newest first: inflation rate and lamports minted, vote credits, total distributed",
+                    "responses": response_set(&["200", "500"]),
+                }
+            },
+            "/rewards/{pubkey}": {
+                "get": {
+                    "summary": "Epoch rewards paid to the owner's stake account (Staking) or to the pubkey as validator commission (Commission)",
+                    "parameters": [
+                        { "name": "pubkey", "in": "path", "required": true, "schema": { "type": "string" } }
+                    ],
+                    "responses": response_set(&["200", "400", "500"]),
+                }
+            },
+            "/snapshot": {
+                "get": {
+                    "summary": "Newest ledger snapshot (magic, sha256, gzip body) for fast bootstrap; slot in the X-Snapshot-Slot header",
+                    "responses": snapshot_responses,
+                }
+            },
+            "/fee_for_message": {
+                "post": {
+                    "summary": "getFeeForMessage: base fee per signature plus ComputeBudget priority fee, in lamports",
+                    "requestBody": {
+                        "required": true,
+                        "content": { "application/json": { "schema": { "$ref": "#/components/schemas/FeeForMessageRequest" } } }
+                    },
+                    "responses": response_set(&["200", "400"]),
+                }
+            },
+            "/mempool": {
+                "get": {
+                    "summary": "Mempool stats: size, distinct senders, fee percentiles (lamports), inserted/evicted/expired counters",
+                    "responses": response_set(&["200", "500"]),
+                }
+            },
+            "/work": {
+                "get": {
+                    "summary": "Open mining work for the node's current leader slot: slot, leader, PoH and parent hashes, Merkle root of the block's transactions, target, and the scrypt header (hex) a big-endian u64 nonce is appended to",
+                    "responses": response_set(&["200", "404"]),
+                }
+            },
+            "/submit_block": {
+                "post": {
+                    "summary": "Submit a nonce for the open work; if its scrypt hash is below the target, the node signs and appends the block",
+                    "requestBody": {
+                        "required": true,
+                        "content": { "application/json": { "schema": { "$ref": "#/components/schemas/SubmitBlockRequest" } } }
+                    },
+                    "responses": response_set(&["200", "400", "409", "422"]),
+                }
+            },
+            "/openapi.json": {
+                "get": {
+                    "summary": "This document",
+                    "responses": { "200": { "description": "OpenAPI description" } },
+                }
+            }
+        },
+        "components": {
+            "schemas": {
+                "SubmitTransactionRequest": {
+                    "type": "object",
+                    "required": ["tx"],
+                    "properties": { "tx": { "type": "string", "description": "Base64-encoded bincode Transaction" } }
+                },
+                "SubmitBlockRequest": {
+                    "type": "object",
+                    "required": ["slot", "nonce"],
+                    "properties": {
+                        "slot": { "type": "integer", "format": "uint64" },
+                        "nonce": { "type": "integer", "format": "uint64" }
+                    }
+                },
+                "FeeForMessageRequest": {
+                    "type": "object",
+                    "required": ["message"],
+                    "properties": { "message": { "type": "string", "description": "Base64-encoded bincode Message" } }
+                },
+                "ApiResponse": {
+                    "type": "object",
+                    "required": ["ok", "signature", "slot", "error"],
+                    "properties": {
+                        "ok": { "type": "boolean" },
+                        "signature": { "type": "string", "nullable": true },
+                        "slot": { "type": "integer", "format": "uint64", "nullable": true },
+                        "data": { "type": "object", "description": "Endpoint-specific payload for queries" },
+                        "error": { "allOf": [{ "$ref": "#/components/schemas/ApiError" }], "nullable": true }
+                    }
+                },
+                "ApiError": {
+                    "type": "object",
+                    "required": ["code", "message"],
+                    "properties": {
+                        "code": { "type": "string", "enum": ErrorCode::ALL },
+                        "message": { "type": "string" }
+                    }
+                }
+            }
+        }
+    })
+}
+
+fn response_set(statuses: &[&str]) -> Value {
+    let mut responses = serde_json::Map::new();
+    for status in statuses {
+        responses.insert(status.to_string(), json!({
+            "description": if *status == "200" { "Success" } else { "Error" },
+            "content": { "application/json": { "schema": { "$ref": "#/components/schemas/ApiResponse" } } }
+        }));
+    }
+    Value::Object(responses)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Position of `code` in [`ErrorCode::ALL`]. The match has no wildcard, so a new variant
+    /// fails to compile here until it is given the next position, which the test then holds ALL to.
+    fn position(code: ErrorCode) -> usize {
+        match code {
+            ErrorCode::InvalidRequest => 0,
+            ErrorCode::InvalidEncoding => 1,
+            ErrorCode::InvalidSignature => 2,
+            ErrorCode::InvalidPubkey => 3,
+            ErrorCode::InvalidInstruction => 4,
+            ErrorCode::DuplicateSignature => 5,
+            ErrorCode::BlockhashNotFound => 6,
+            ErrorCode::InsufficientFunds => 7,
+            ErrorCode::InsufficientFundsForRent => 8,
+            ErrorCode::UnknownSender => 9,
+            ErrorCode::AirdropLimitExceeded => 10,
+            ErrorCode::MaliciousBlock => 11,
+            ErrorCode::StaleSlot => 12,
+            ErrorCode::ValidatorJailed => 13,
+            ErrorCode::InvalidWork => 14,
+            ErrorCode::MempoolFull => 15,
+            ErrorCode::SenderLimitExceeded => 16,
+            ErrorCode::NotFound => 17,
+            ErrorCode::MethodNotAllowed => 18,
+            ErrorCode::StorageFailure => 19,
+            ErrorCode::Internal => 20,
+        }
+    }
+
+    #[test]
+    fn all_lists_every_error_code_in_order() {
+        for (i, code) in ErrorCode::ALL.iter().enumerate() {
+            assert_eq!(position(*code), i, "{:?} out of place in ErrorCode::ALL", code);
+            assert_eq!(*code as usize, i, "ErrorCode::ALL does not follow the declaration order");
+        }
+        assert_eq!(ErrorCode::ALL.len(), position(ErrorCode::Internal) + 1, "ErrorCode::ALL is missing codes");
+    }
+
+    /// Status and JSON body of a reply.
+    async fn reply_parts(reply: impl Reply) -> (StatusCode, Value) {
+        let response = reply.into_response();
+        let status = response.status();
+        let body = warp::hyper::body::to_bytes(response.into_body()).await.unwrap();
+        (status, serde_json::from_slice(&body).unwrap())
+    }
+
+    #[tokio::test]
+    async fn failures_carry_their_code_and_status() {
+        let error = LedgerError::InsufficientFunds { account: "payer".to_string(), balance: 1, required: 2 };
+        let (status, body) = reply_parts(ApiResponse::from_ledger_error(&error).into_reply()).await;
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(body["ok"], false);
+        assert_eq!(body["error"]["code"], "INSUFFICIENT_FUNDS");
+        assert_eq!(body["error"]["message"], error.to_string());
+
+        let error = ConsensusError::StaleWork { slot: 5, open: Some(6) };
+        let (status, body) = reply_parts(ApiResponse::from_consensus_error(&error).into_reply()).await;
+        assert_eq!(status, StatusCode::CONFLICT);
+        assert_eq!(body["error"]["code"], "STALE_SLOT");
+    }
+
+    #[tokio::test]
+    async fn success_is_ok_with_data() {
+        let response = ApiResponse::success(None, Some(3)).with_signature("sig".to_string()).with_data(json!({ "n": 1 }));
+        let (status, body) = reply_parts(response.into_reply()).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body, json!({ "ok": true, "signature": "sig", "slot": 3, "data": { "n": 1 }, "error": null }));
+    }
+
+    #[tokio::test]
+    async fn unknown_endpoints_are_json_not_found() {
+        let reply = handle_rejection(warp::reject::not_found()).await.unwrap();
+        let (status, body) = reply_parts(reply).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        assert_eq!(body["error"]["code"], "NOT_FOUND");
+    }
+}
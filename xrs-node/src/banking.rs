@@ -0,0 +1,266 @@
+// Patent Pending Copyright © 2025 Xeris Web Co. All rights reserved.
+// XerisCoin Banking Stage - Parallel TX Execution with Account-Lock Scheduling
+// Non-conflicting batches run across a thread pool, commits stay in block order (Local Alpha)
+
+use std::collections::HashMap;
+use std::time::Instant;
+use solana_sdk::{pubkey::Pubkey, signature::{Keypair, Signer}, transaction::Transaction};
+#[allow(deprecated)]
+use solana_sdk::system_instruction;
+use log::{info, debug};
+use crate::accounts;
+use crate::error::LedgerError;
+use crate::ledger::{ExecutedTx, Ledger};
+
+/// Batches smaller than this execute inline; spawning threads costs more than it saves.
+const MIN_PARALLEL_BATCH: usize = 64;
+
+pub struct BankingStage {
+    threads: usize,
+}
+
+/// Write and read account sets a transaction locks while executing.
+fn account_locks(tx: &Transaction) -> (Vec<Pubkey>, Vec<Pubkey>) {
+    let mut writes = Vec::new();
+    let mut reads = Vec::new();
+    for (i, key) in tx.message.account_keys.iter().enumerate() {
+        if tx.message.is_maybe_writable(i, None) {
+            writes.push(*key);
+        } else {
+            reads.push(*key);
+        }
+    }
+    (writes, reads)
+}
+
+impl BankingStage {
+    pub fn new(threads: usize) -> Self {
+        BankingStage { threads: threads.max(1) }
+    }
+
+    pub fn default_threads() -> usize {
+        std::thread::available_parallelism().map(|n| n.get()).unwrap_or(1)
+    }
+
+    /// Groups transaction indices into batches with no write/write or read/write account conflicts.
+    /// Each transaction lands in the batch after the last one it conflicts with, so conflicting
+    /// transactions always execute in block order.
+    pub fn schedule(txs: &[Transaction]) -> Vec<Vec<usize>> {
+        let mut batches: Vec<Vec<usize>> = Vec::new();
+        let mut last_write: HashMap<Pubkey, usize> = HashMap::new();
+        let mut last_read: HashMap<Pubkey, usize> = HashMap::new();
+        for (i, tx) in txs.iter().enumerate() {
+            let (writes, reads) = account_locks(tx);
+            let mut batch = 0;
+            for key in &writes {
+                if let Some(&b) = last_write.get(key) {
+                    batch = batch.max(b + 1);
+                }
+                if let Some(&b) = last_read.get(key) {
+                    batch = batch.max(b + 1);
+                }
+            }
+            for key in &reads {
+                if let Some(&b) = last_write.get(key) {
+                    batch = batch.max(b + 1);
+                }
+            }
+            for key in writes {
+                let entry = last_write.entry(key).or_insert(batch);
+                *entry = (*entry).max(batch);
+            }
+            for key in reads {
+                let entry = last_read.entry(key).or_insert(batch);
+                *entry = (*entry).max(batch);
+            }
+            if batches.len() <= batch {
+                batches.resize_with(batch + 1, Vec::new);
+            }
+            batches[batch].push(i);
+        }
+        batches
+    }
+
+    fn execute_batch(&self, ledger: &Ledger, txs: &[Transaction], batch: &[usize], slot: u64) -> Vec<(usize, Result<ExecutedTx, LedgerError>)> {
+        if self.threads == 1 || batch.len() < MIN_PARALLEL_BATCH {
+            return batch.iter().map(|&i| (i, ledger.execute_transaction(&txs[i], slot))).collect();
+        }
+        let chunk_size = batch.len().div_ceil(self.threads);
+        std::thread::scope(|scope| {
+            let workers: Vec<_> = batch
+                .chunks(chunk_size)
+                .map(|chunk| {
+                    scope.spawn(move || {
+                        chunk.iter().map(|&i| (i, ledger.execute_transaction(&txs[i], slot))).collect::<Vec<_>>()
+                    })
+                })
+                .collect();
+            workers
+                .into_iter()
+                .flat_map(|worker| worker.join().expect("Banking stage worker panicked"))
+                .collect()
+        })
+    }
+
+    /// Executes a block's transactions batch by batch and commits each batch in index order.
+    /// Returns the transactions that committed, in their original block order.
+    pub fn execute(&self, ledger: &mut Ledger, txs: Vec<Transaction>, slot: u64) -> Result<Vec<Transaction>, LedgerError> {
+        if txs.is_empty() {
+            return Ok(txs);
+        }
+        let batches = Self::schedule(&txs);
+        let mut committed = vec![false; txs.len()];
+        for batch in &batches {
+            let mut results = self.execute_batch(ledger, &txs, batch, slot);
+            results.sort_by_key(|(i, _)| *i);
+            let mut executed = Vec::with_capacity(results.len());
+            for (i, result) in results {
+                match result {
+                    Ok(tx) => {
+                        committed[i] = true;
+                        executed.push(tx);
+                    }
+                    Err(e) => debug!("Local Alpha: Dropped tx {} from block {}: {}", txs[i].signatures[0], slot, e),
+                }
+            }
+            ledger.commit_transactions(executed, slot)?;
+        }
+        let total = txs.len();
+        let included: Vec<Transaction> = txs
+            .into_iter()
+            .zip(committed)
+            .filter_map(|(tx, ok)| ok.then_some(tx))
+            .collect();
+        info!(
+            "Local Alpha: Banking stage executed {}/{} txs in {} batches ({} threads)",
+            included.len(),
+            total,
+            batches.len(),
+            self.threads
+        );
+        Ok(included)
+    }
+}
+
+/// Compares the serial `add_transaction` path against the banking stage on `num_txs`
+/// independent transfers, each on a freshly funded temporary ledger.
+pub fn run_benchmark(num_txs: usize, threads: usize) -> Result<(), LedgerError> {
+    let senders: Vec<Keypair> = (0..num_txs).map(|_| Keypair::new()).collect();
+    let setup = |label: &str| -> Result<(Ledger, Vec<Transaction>), LedgerError> {
+        let path = std::env::temp_dir().join(format!("xrs-bench-{}-{}.dat", label, std::process::id()));
+        let path = path.to_string_lossy().to_string();
+        let mut ledger = Ledger::in_memory(path)?;
+        for sender in &senders {
+            ledger.airdrop(&sender.pubkey().to_string(), 20 * 1_000_000_000)?;
+        }
+        let blockhash = ledger.latest_blockhash();
+        let txs = senders
+            .iter()
+            .map(|sender| {
+                // New recipients must be funded to the rent-exempt minimum
+                let ix = system_instruction::transfer(&sender.pubkey(), &Pubkey::new_unique(), accounts::minimum_balance(0));
+                Transaction::new_signed_with_payer(&[ix], Some(&sender.pubkey()), &[sender], blockhash)
+            })
+            .collect();
+        Ok((ledger, txs))
+    };
+    let report = |label: &str, committed: usize, started: Instant| {
+        let secs = started.elapsed().as_secs_f64();
+        info!(
+            "Local Alpha: Bench {}: {} txs in {:.3}s = {:.0} TPS",
+            label,
+            committed,
+            secs,
+            committed as f64 / secs.max(f64::EPSILON)
+        );
+    };
+
+    let (mut ledger, txs) = setup("serial")?;
+    let started = Instant::now();
+    let mut committed = 0;
+    for tx in txs {
+        if ledger.add_transaction(tx, 1).is_ok() {
+            committed += 1;
+        }
+    }
+    report("serial add_transaction", committed, started);
+
+    let mut stage_threads = vec![1];
+    if threads > 1 {
+        stage_threads.push(threads);
+    }
+    for stage_threads in stage_threads {
+        let (mut ledger, txs) = setup(&format!("banking{}", stage_threads))?;
+        let started = Instant::now();
+        let committed = BankingStage::new(stage_threads).execute(&mut ledger, txs, 1)?.len();
+        report(&format!("banking stage ({} threads)", stage_threads), committed, started);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ledger::test_support;
+
+    const XRS: u64 = 1_000_000_000;
+
+    /// A ledger with every sender funded and `blockhash` recent, identical for each execution path.
+    fn funded_ledger(label: &str, senders: &[Keypair], blockhash: solana_sdk::hash::Hash) -> Ledger {
+        let mut ledger = test_support::funded_ledger(label, senders.iter().map(|sender| sender.pubkey()), 20 * XRS);
+        test_support::add_blockhash(&mut ledger, blockhash);
+        ledger
+    }
+
+    #[test]
+    fn parallel_execution_matches_serial() {
+        let senders: Vec<Keypair> = (0..64).map(|_| Keypair::new()).collect();
+        let shared = Pubkey::new_unique();
+        // Each ledger boots with its own blockhash, so both are given this one
+        let blockhash = solana_sdk::hash::Hash::new_unique();
+        let transfer = |from: &Keypair, to: &Pubkey, lamports: u64| test_support::transfer(from, to, lamports, blockhash);
+        // Independent transfers, a hot shared recipient, chains through the next sender and
+        // overdrafts that must fail on both paths
+        let mut txs: Vec<Transaction> =
+            senders.iter().map(|sender| transfer(sender, &Pubkey::new_unique(), accounts::minimum_balance(0))).collect();
+        for (i, sender) in senders.iter().enumerate() {
+            txs.push(transfer(sender, &shared, XRS + i as u64));
+            txs.push(transfer(sender, &senders[(i + 1) % senders.len()].pubkey(), 3 * XRS));
+            if i % 5 == 0 {
+                txs.push(transfer(sender, &shared, 100 * XRS));
+            }
+        }
+        let keys: Vec<Pubkey> = txs.iter().flat_map(|tx| tx.message.account_keys.clone()).collect();
+
+        let mut serial = funded_ledger("serial", &senders, blockhash);
+        let serial_committed: Vec<Transaction> =
+            txs.iter().filter(|tx| serial.add_transaction((*tx).clone(), 1).is_ok()).cloned().collect();
+
+        let mut parallel = funded_ledger("parallel", &senders, blockhash);
+        assert!(BankingStage::schedule(&txs).iter().any(|batch| batch.len() >= MIN_PARALLEL_BATCH));
+        let parallel_committed = BankingStage::new(4).execute(&mut parallel, txs.clone(), 1).unwrap();
+
+        // Every transfer but the overdrafts lands
+        assert_eq!(serial_committed.len(), 3 * senders.len());
+        assert_eq!(parallel_committed.len(), serial_committed.len());
+        for (a, b) in parallel_committed.iter().zip(&serial_committed) {
+            assert_eq!(a.signatures[0], b.signatures[0]);
+        }
+        for key in &keys {
+            assert_eq!(parallel.get_account(key), serial.get_account(key), "account {}", key);
+        }
+    }
+
+    #[test]
+    fn schedule_orders_conflicting_transactions() {
+        let (a, b) = (Keypair::new(), Keypair::new());
+        let blockhash = solana_sdk::hash::Hash::new_unique();
+        let transfer = |from: &Keypair, to: &Pubkey| test_support::transfer(from, to, 1, blockhash);
+        let txs = vec![
+            transfer(&a, &Pubkey::new_unique()),
+            transfer(&b, &Pubkey::new_unique()),
+            transfer(&a, &b.pubkey()),
+        ];
+        assert_eq!(BankingStage::schedule(&txs), vec![vec![0, 1], vec![2]]);
+    }
+}
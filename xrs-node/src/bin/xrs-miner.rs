@@ -0,0 +1,141 @@
+// Patent Pending Copyright © 2025 Xeris Web Co. All rights reserved.
+// XerisCoin Standalone Miner - Solves a Node's Leader Slots over /work and /submit_block
+// Local Alpha: talks plain HTTP to 127.0.0.1 only
+
+#[path = "../miner.rs"]
+mod miner;
+
+use clap::{Arg, Command};
+use log::{debug, error, info};
+use miner::MinerPool;
+use serde_json::Value;
+use std::io::{self, Read, Write};
+use std::net::TcpStream;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// How often the watcher thread polls the node for new work.
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+const HASHRATE_LOG_INTERVAL: Duration = Duration::from_secs(10);
+
+/// The open work the node serves on /work.
+#[derive(Clone, Debug, PartialEq)]
+struct Work {
+    slot: u64,
+    header: Vec<u8>,
+    target: Vec<u8>,
+}
+
+/// Sends one HTTP/1.0 request to the node and parses its JSON reply, whatever the status.
+fn request(node: &str, method: &str, path: &str, body: Option<&str>) -> io::Result<Value> {
+    let mut stream = TcpStream::connect(node)?;
+    stream.set_read_timeout(Some(Duration::from_secs(5)))?;
+    let body = body.unwrap_or_default();
+    write!(
+        stream,
+        "{} {} HTTP/1.0\r\nHost: {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\r\n{}",
+        method,
+        path,
+        node,
+        body.len(),
+        body
+    )?;
+    let mut response = Vec::new();
+    stream.read_to_end(&mut response)?;
+    let header_end = response
+        .windows(4)
+        .position(|w| w == b"\r\n\r\n")
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "malformed HTTP response"))?;
+    serde_json::from_slice(&response[header_end + 4..]).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+}
+
+/// The node's open work, or `None` while it is not leading a slot.
+fn fetch_work(node: &str) -> io::Result<Option<Work>> {
+    let reply = request(node, "GET", "/work", None)?;
+    if !reply["ok"].as_bool().unwrap_or(false) {
+        return Ok(None);
+    }
+    let data = &reply["data"];
+    let decode = |field: &str| {
+        hex::decode(data[field].as_str().unwrap_or_default()).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    };
+    Ok(Some(Work {
+        slot: data["slot"].as_u64().ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "work without slot"))?,
+        header: decode("header")?,
+        target: decode("target")?,
+    }))
+}
+
+fn main() {
+    env_logger::init();
+    let matches = Command::new("XRS Miner - Local Alpha v0.1.0 - Patent Pending © 2025 Xeris")
+        .arg(
+            Arg::new("node")
+                .long("node")
+                .value_name("host:port")
+                .value_parser(clap::value_parser!(String))
+                .default_value("127.0.0.1:4001")
+                .help("Node HTTP endpoint serving /work and /submit_block"),
+        )
+        .arg(
+            Arg::new("threads")
+                .long("threads")
+                .value_name("threads")
+                .value_parser(clap::value_parser!(usize))
+                .help("Mining threads (default: one per core)"),
+        )
+        .get_matches();
+    let node = matches.get_one::<String>("node").unwrap().clone();
+    let threads = matches.get_one::<usize>("threads").copied().unwrap_or_else(MinerPool::default_threads);
+    let pool = MinerPool::new(threads);
+    info!("Local Alpha: XRS miner started on {} threads against http://{} (Patent Pending)", pool.threads(), node);
+
+    // The watcher keeps the latest open work so a search is cancelled as soon as the slot moves on
+    let latest: Arc<Mutex<Option<Work>>> = Arc::new(Mutex::new(None));
+    {
+        let (latest, node) = (latest.clone(), node.clone());
+        thread::spawn(move || loop {
+            match fetch_work(&node) {
+                Ok(work) => *latest.lock().unwrap() = work,
+                Err(e) => {
+                    debug!("Local Alpha: Failed to fetch work from {}: {}", node, e);
+                    *latest.lock().unwrap() = None;
+                }
+            }
+            thread::sleep(POLL_INTERVAL);
+        });
+    }
+
+    let mut submitted: Option<u64> = None;
+    let mut last_log = Instant::now();
+    loop {
+        if last_log.elapsed() >= HASHRATE_LOG_INTERVAL {
+            info!("Local Alpha: Hashrate {:.1} H/s", pool.sample_hashrate());
+            last_log = Instant::now();
+        }
+        let current = latest.lock().unwrap().clone();
+        let work = match current {
+            Some(work) if submitted != Some(work.slot) => work,
+            _ => {
+                thread::sleep(POLL_INTERVAL);
+                continue;
+            }
+        };
+        debug!("Local Alpha: Mining slot {}", work.slot);
+        let solved = pool.solve(&work.header, &work.target, || latest.lock().unwrap().as_ref() != Some(&work));
+        let Some((nonce, hash)) = solved else {
+            debug!("Local Alpha: Work for slot {} withdrawn before a solution", work.slot);
+            continue;
+        };
+        submitted = Some(work.slot);
+        let body = serde_json::json!({ "slot": work.slot, "nonce": nonce }).to_string();
+        match request(&node, "POST", "/submit_block", Some(&body)) {
+            Ok(reply) if reply["ok"].as_bool().unwrap_or(false) => {
+                info!("Local Alpha: Block accepted: slot={}, hash={}, nonce={}", work.slot, hex::encode(&hash), nonce);
+            }
+            Ok(reply) => error!("Local Alpha: Block for slot {} refused: {}", work.slot, reply["error"]),
+            Err(e) => error!("Local Alpha: Failed to submit block for slot {}: {}", work.slot, e),
+        }
+    }
+}
@@ -0,0 +1,201 @@
+// Patent Pending Copyright © 2025 Xeris Web Co. All rights reserved.
+// XerisCoin Error Types - Ledger & Consensus Failure Modes (Local Alpha)
+// Lets RPC, miner API and validator loop branch on failures programmatically
+
+use std::fmt;
+
+#[derive(Debug)]
+pub enum LedgerError {
+    InsufficientFunds { account: String, balance: u64, required: u64 },
+    DuplicateSignature(String),
+    BlockhashNotFound(String),
+    InvalidSignature(String),
+    UnknownSender(String),
+    InvalidPubkey(String),
+    InvalidInstruction(String),
+    InsufficientFundsForRent { account: String, lamports: u64, minimum: u64 },
+    AirdropLimitExceeded { requested: u64, available: u64 },
+    MaliciousBlock { slot: u64 },
+    StaleSlot { slot: u64, last_slot: u64 },
+    ValidatorJailed { validator: String, until_epoch: u64 },
+    SlotNotFound(u64),
+    SignatureNotFound(String),
+    #[allow(dead_code)]
+    FaucetUnavailable,
+    Io(std::io::Error),
+    Storage(String),
+}
+
+impl fmt::Display for LedgerError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LedgerError::InsufficientFunds { account, balance, required } => write!(
+                f,
+                "Insufficient funds for {}: balance {} lamports, required {} lamports",
+                account, balance, required
+            ),
+            LedgerError::DuplicateSignature(sig) => write!(f, "Double-spend detected: {}", sig),
+            LedgerError::BlockhashNotFound(hash) => write!(f, "Blockhash not found or expired: {}", hash),
+            LedgerError::InvalidSignature(sig) => write!(f, "Invalid signature: {}", sig),
+            LedgerError::UnknownSender(sender) => write!(f, "Sender not found: {}", sender),
+            LedgerError::InvalidPubkey(address) => write!(f, "Invalid pubkey: {}", address),
+            LedgerError::InvalidInstruction(reason) => write!(f, "Invalid instruction: {}", reason),
+            LedgerError::InsufficientFundsForRent { account, lamports, minimum } => write!(
+                f,
+                "Account {} would hold {} lamports, below the rent-exempt minimum of {} lamports",
+                account, lamports, minimum
+            ),
+            LedgerError::AirdropLimitExceeded { requested, available } => write!(
+                f,
+                "Airdrop limit exceeded: requested {} lamports, available {} lamports",
+                requested, available
+            ),
+            LedgerError::MaliciousBlock { slot } => write!(f, "Malicious block detected at slot {}", slot),
+            LedgerError::StaleSlot { slot, last_slot } => {
+                write!(f, "Stale slot {}: ledger is already at slot {}", slot, last_slot)
+            }
+            LedgerError::ValidatorJailed { validator, until_epoch } => {
+                write!(f, "Validator {} is jailed until epoch {}", validator, until_epoch)
+            }
+            LedgerError::SlotNotFound(slot) => write!(f, "No block at or before slot {}", slot),
+            LedgerError::SignatureNotFound(sig) => write!(f, "Transaction not found in any block: {}", sig),
+            LedgerError::FaucetUnavailable => write!(f, "Faucet only available in testnet"),
+            LedgerError::Io(e) => write!(f, "Ledger I/O failure: {}", e),
+            LedgerError::Storage(e) => write!(f, "Ledger storage failure: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for LedgerError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            LedgerError::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for LedgerError {
+    fn from(e: std::io::Error) -> Self {
+        LedgerError::Io(e)
+    }
+}
+
+#[derive(Debug)]
+pub enum ConsensusError {
+    InsufficientStake { validator: String, stake: u64, required: u64 },
+    InsufficientVotes { votes: u64, required: u64 },
+    Pow(String),
+    /// No open work for `slot`: another miner solved it, or the node is not leading it.
+    StaleWork { slot: u64, open: Option<u64> },
+    /// The nonce's hash is not below the work's target.
+    InvalidWork { slot: u64, nonce: u64 },
+    Ledger(LedgerError),
+}
+
+impl fmt::Display for ConsensusError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConsensusError::InsufficientStake { validator, stake, required } => write!(
+                f,
+                "Insufficient stake to propose block: {} has {} lamports, required {} lamports",
+                validator, stake, required
+            ),
+            ConsensusError::InsufficientVotes { votes, required } => write!(
+                f,
+                "Insufficient stake votes: {} lamports, required {} lamports",
+                votes, required
+            ),
+            ConsensusError::Pow(e) => write!(f, "Proof-of-work failure: {}", e),
+            ConsensusError::StaleWork { slot, open: Some(open) } => {
+                write!(f, "No open work for slot {}: the open work is for slot {}", slot, open)
+            }
+            ConsensusError::StaleWork { slot, open: None } => write!(f, "No open work for slot {}", slot),
+            ConsensusError::InvalidWork { slot, nonce } => write!(f, "Nonce {} does not meet the target for slot {}", nonce, slot),
+            ConsensusError::Ledger(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl std::error::Error for ConsensusError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ConsensusError::Ledger(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<LedgerError> for ConsensusError {
+    fn from(e: LedgerError) -> Self {
+        ConsensusError::Ledger(e)
+    }
+}
+
+impl From<scrypt::errors::InvalidParams> for ConsensusError {
+    fn from(e: scrypt::errors::InvalidParams) -> Self {
+        ConsensusError::Pow(e.to_string())
+    }
+}
+
+impl From<scrypt::errors::InvalidOutputLen> for ConsensusError {
+    fn from(e: scrypt::errors::InvalidOutputLen) -> Self {
+        ConsensusError::Pow(e.to_string())
+    }
+}
+
+#[derive(Debug)]
+pub enum MempoolError {
+    MissingSignature,
+    Duplicate(String),
+    Full { min_fee: u64 },
+    SenderLimit { sender: String, limit: usize },
+}
+
+impl fmt::Display for MempoolError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MempoolError::MissingSignature => write!(f, "Transaction has no signatures"),
+            MempoolError::Duplicate(sig) => write!(f, "Transaction already in mempool: {}", sig),
+            MempoolError::Full { min_fee } => {
+                write!(f, "Mempool full: fee must exceed {} lamports to replace the lowest entry", min_fee)
+            }
+            MempoolError::SenderLimit { sender, limit } => {
+                write!(f, "Sender {} already has {} pending transactions", sender, limit)
+            }
+        }
+    }
+}
+
+impl std::error::Error for MempoolError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::error::Error;
+
+    #[test]
+    fn ledger_errors_pass_through_consensus_errors() {
+        let ledger = LedgerError::StaleSlot { slot: 4, last_slot: 9 };
+        let message = ledger.to_string();
+        let consensus = ConsensusError::from(ledger);
+        assert!(matches!(consensus, ConsensusError::Ledger(LedgerError::StaleSlot { slot: 4, last_slot: 9 })));
+        assert_eq!(consensus.to_string(), message);
+        assert_eq!(consensus.source().map(|e| e.to_string()), Some(message));
+    }
+
+    #[test]
+    fn io_failures_keep_their_cause() {
+        let ledger = LedgerError::from(std::io::Error::new(std::io::ErrorKind::PermissionDenied, "read-only disk"));
+        assert!(matches!(&ledger, LedgerError::Io(e) if e.kind() == std::io::ErrorKind::PermissionDenied));
+        assert_eq!(ledger.to_string(), "Ledger I/O failure: read-only disk");
+        assert_eq!(ledger.source().map(|e| e.to_string()), Some("read-only disk".to_string()));
+    }
+
+    #[test]
+    fn scrypt_failures_are_pow_errors() {
+        let consensus = ConsensusError::from(scrypt::Params::new(64, 1, 1).unwrap_err());
+        assert!(matches!(consensus, ConsensusError::Pow(_)));
+        assert!(consensus.source().is_none());
+    }
+}
@@ -1,16 +1,20 @@
 // Patent Pending Copyright © 2025 Xeris Web Co. All rights reserved.
 // XerisCoin Explorer - Local-Only Warp Server (127.0.0.1:8081)
-// Serves /blocks & /balances JSON from Ledger (Triple Consensus View)
+// Serves /blocks, /balances & /validators JSON from Ledger, /metrics, /ws Subscriptions (Triple Consensus View)
 
+use std::collections::HashMap;
 use std::sync::{Arc, Mutex};
 use warp::Filter;
 use crate::ledger::Ledger;
+use crate::pubsub::{self, PubSub};
+use crate::staking;
 use log::{info, debug};
-use serde_json;
+use prometheus::{Encoder, Registry, TextEncoder};
 
-pub async fn start_explorer(ledger: Arc<Mutex<Ledger>>) -> Result<(), Box<dyn std::error::Error>> {
+pub async fn start_explorer(ledger: Arc<Mutex<Ledger>>, pubsub: PubSub, registry: Registry) -> Result<(), Box<dyn std::error::Error>> {
     let ledger_blocks = ledger.clone();
     let ledger_balances = ledger.clone();
+    let ledger_validators = ledger.clone();
 
     let blocks = warp::path("blocks").map(move || {
         debug!("Local Alpha: Handling /blocks request");
@@ -22,13 +26,57 @@ pub async fn start_explorer(ledger: Arc<Mutex<Ledger>>) -> Result<(), Box<dyn st
     let balances = warp::path("balances").map(move || {
         debug!("Local Alpha: Handling /balances request");
         let ledger = ledger_balances.lock().unwrap();
-        debug!("Local Alpha: Balances in ledger: {:?}", ledger.balances);
-        serde_json::to_string(&ledger.balances).unwrap()
+        let balances: HashMap<String, u64> = ledger
+            .get_accounts()
+            .iter()
+            .map(|(pubkey, account)| (pubkey.to_string(), account.lamports))
+            .collect();
+        debug!("Local Alpha: Balances in ledger: {:?}", balances);
+        serde_json::to_string(&balances).unwrap()
     });
 
-    let routes = blocks.or(balances);
+    let validators = warp::path("validators").map(move || {
+        debug!("Local Alpha: Handling /validators request");
+        let ledger = ledger_validators.lock().unwrap();
+        let slot = ledger.get_last_block().map(|b| b.slot).unwrap_or(0);
+        let effective = ledger.get_effective_stakes();
+        let active = ledger.get_active_validators();
+        let validators: Vec<serde_json::Value> = ledger
+            .get_validators()
+            .into_iter()
+            .map(|validator| {
+                let stake = ledger.get_stakes().get(&validator.identity).copied().unwrap_or(0);
+                let effective_stake = effective.get(&validator.identity).copied().unwrap_or(0);
+                serde_json::json!({
+                    "identity": validator.identity.to_string(),
+                    "stake": stake,
+                    "effective_stake": effective_stake,
+                    "reputation": validator.reputation,
+                    "leader_weight": validator.weighted_stake(effective_stake),
+                    "network_address": validator.registration.as_ref().map(|r| r.network_address.clone()),
+                    "active": active.contains(&validator.identity),
+                    "missed_slots": validator.missed_slots,
+                    "produced_slots": validator.produced_slots,
+                    "offenses": validator.offenses,
+                    "jailed": validator.is_jailed(staking::epoch(slot)),
+                })
+            })
+            .collect();
+        serde_json::to_string(&validators).unwrap()
+    });
+
+    let metrics = warp::path("metrics").map(move || {
+        let mut buffer = Vec::new();
+        if let Err(e) = TextEncoder::new().encode(&registry.gather(), &mut buffer) {
+            debug!("Local Alpha: Metrics encoding failed: {}", e);
+        }
+        String::from_utf8(buffer).unwrap_or_default()
+    });
+
+    let routes = blocks.or(balances).or(validators).or(metrics).or(pubsub::ws_route(pubsub));
     let addr: std::net::SocketAddr = "127.0.0.1:8081".parse().expect("Invalid address");
     info!("Local Alpha: Blockchain explorer started on http://127.0.0.1:8081 (Patent Pending)");
+    info!("Local Alpha: WebSocket subscriptions on ws://127.0.0.1:8081/ws (slot, block, account, signature)");
     warp::serve(routes).run(addr).await;
     Ok(())
 }
\ No newline at end of file
@@ -0,0 +1,147 @@
+// Patent Pending Copyright © 2025 Xeris Web Co. All rights reserved.
+// XerisCoin Fee Market - Base Fee per Signature + ComputeBudget Priority Fee
+// Integer-only; split between burn and block leader (Local Alpha)
+
+#[allow(deprecated)]
+use solana_sdk::{compute_budget, message::Message, pubkey::Pubkey};
+use serde::Serialize;
+
+pub const LAMPORTS_PER_SIGNATURE: u64 = 5_000;
+pub const DEFAULT_COMPUTE_UNIT_LIMIT: u32 = 200_000;
+pub const MAX_COMPUTE_UNIT_LIMIT: u32 = 1_400_000;
+pub const MICRO_LAMPORTS_PER_LAMPORT: u128 = 1_000_000;
+/// Share of every fee that is burned; the remainder goes to the block leader.
+pub const BURN_PERCENT: u64 = 50;
+
+// ComputeBudgetInstruction borsh discriminants
+const SET_COMPUTE_UNIT_LIMIT: u8 = 2;
+const SET_COMPUTE_UNIT_PRICE: u8 = 3;
+
+#[derive(Serialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct FeeDetails {
+    pub base_fee: u64,
+    pub priority_fee: u64,
+    pub compute_unit_limit: u32,
+    pub compute_unit_price: u64,
+}
+
+impl FeeDetails {
+    pub fn total(&self) -> u64 {
+        self.base_fee.saturating_add(self.priority_fee)
+    }
+
+    pub fn burned(&self) -> u64 {
+        (self.total() as u128 * BURN_PERCENT as u128 / 100) as u64
+    }
+
+    pub fn leader_share(&self) -> u64 {
+        self.total() - self.burned()
+    }
+}
+
+pub fn is_compute_budget_program(program_id: &Pubkey) -> bool {
+    *program_id == compute_budget::id()
+}
+
+/// Computes the fee a message will pay: signatures * base fee, plus
+/// `ceil(compute_unit_price * compute_unit_limit / 1e6)` from ComputeBudget instructions.
+pub fn calculate_fee(message: &Message) -> FeeDetails {
+    let mut compute_unit_limit = DEFAULT_COMPUTE_UNIT_LIMIT;
+    let mut compute_unit_price = 0u64;
+    for ix in &message.instructions {
+        let is_compute_budget = message
+            .account_keys
+            .get(ix.program_id_index as usize)
+            .map(is_compute_budget_program)
+            .unwrap_or(false);
+        if !is_compute_budget {
+            continue;
+        }
+        match ix.data.split_first() {
+            Some((&SET_COMPUTE_UNIT_LIMIT, rest)) => {
+                if let Ok(bytes) = <[u8; 4]>::try_from(rest) {
+                    compute_unit_limit = u32::from_le_bytes(bytes).min(MAX_COMPUTE_UNIT_LIMIT);
+                }
+            }
+            Some((&SET_COMPUTE_UNIT_PRICE, rest)) => {
+                if let Ok(bytes) = <[u8; 8]>::try_from(rest) {
+                    compute_unit_price = u64::from_le_bytes(bytes);
+                }
+            }
+            _ => {}
+        }
+    }
+    let micro_lamports = compute_unit_price as u128 * compute_unit_limit as u128;
+    let priority_fee = micro_lamports.div_ceil(MICRO_LAMPORTS_PER_LAMPORT);
+    FeeDetails {
+        base_fee: message.header.num_required_signatures as u64 * LAMPORTS_PER_SIGNATURE,
+        priority_fee: u64::try_from(priority_fee).unwrap_or(u64::MAX),
+        compute_unit_limit,
+        compute_unit_price,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[allow(deprecated)]
+    use solana_sdk::{compute_budget::ComputeBudgetInstruction, instruction::Instruction, system_instruction};
+
+    fn fee_of(budget: Vec<Instruction>) -> FeeDetails {
+        let payer = Pubkey::new_unique();
+        let mut instructions = budget;
+        instructions.push(system_instruction::transfer(&payer, &Pubkey::new_unique(), 1));
+        calculate_fee(&Message::new(&instructions, Some(&payer)))
+    }
+
+    #[test]
+    fn base_fee_without_compute_budget() {
+        let fee = fee_of(Vec::new());
+        assert_eq!(fee.base_fee, LAMPORTS_PER_SIGNATURE);
+        assert_eq!(fee.priority_fee, 0);
+        assert_eq!(fee.compute_unit_limit, DEFAULT_COMPUTE_UNIT_LIMIT);
+        assert_eq!(fee.total(), LAMPORTS_PER_SIGNATURE);
+    }
+
+    #[test]
+    fn priority_fee_rounds_price_times_limit_up() {
+        // 3 micro-lamports over 300k units is 0.9 lamports, charged as 1
+        let fee = fee_of(vec![
+            ComputeBudgetInstruction::set_compute_unit_limit(300_000),
+            ComputeBudgetInstruction::set_compute_unit_price(3),
+        ]);
+        assert_eq!((fee.compute_unit_limit, fee.compute_unit_price, fee.priority_fee), (300_000, 3, 1));
+        // Exact multiples are not rounded
+        let fee = fee_of(vec![ComputeBudgetInstruction::set_compute_unit_price(5)]);
+        assert_eq!(fee.priority_fee, 1);
+        let fee = fee_of(vec![ComputeBudgetInstruction::set_compute_unit_price(10_000)]);
+        assert_eq!(fee.priority_fee, 2_000);
+        // The limit is capped before it is priced
+        let fee = fee_of(vec![
+            ComputeBudgetInstruction::set_compute_unit_limit(u32::MAX),
+            ComputeBudgetInstruction::set_compute_unit_price(1_000_000),
+        ]);
+        assert_eq!((fee.compute_unit_limit, fee.priority_fee), (MAX_COMPUTE_UNIT_LIMIT, MAX_COMPUTE_UNIT_LIMIT as u64));
+    }
+
+    #[test]
+    fn malformed_compute_budget_is_ignored() {
+        let malformed = |data: Vec<u8>| Instruction::new_with_bytes(compute_budget::id(), &data, Vec::new());
+        let fee = fee_of(vec![
+            malformed(vec![SET_COMPUTE_UNIT_LIMIT, 1, 2]),
+            malformed(vec![SET_COMPUTE_UNIT_PRICE, 0, 0, 0, 0, 0, 0, 0, 1, 9]),
+            malformed(vec![42]),
+            malformed(Vec::new()),
+        ]);
+        assert_eq!(fee, fee_of(Vec::new()));
+    }
+
+    #[test]
+    fn burn_and_leader_share_add_up_to_the_fee() {
+        for price in [0, 1, 7, 1_234_567, u64::MAX] {
+            let fee = fee_of(vec![ComputeBudgetInstruction::set_compute_unit_price(price)]);
+            assert_eq!(fee.burned() + fee.leader_share(), fee.total(), "price {}", price);
+            assert_eq!(fee.burned() as u128, fee.total() as u128 * BURN_PERCENT as u128 / 100);
+        }
+    }
+}
@@ -1,6 +1,36 @@
 use std::fs::{self, File};
 use std::io::Write;
 use std::path::Path;
+use serde::Deserialize;
+use crate::staking::StakeConfig;
+
+pub const GENESIS_PATH: &str = "./xrs-genesis.json";
+
+/// Chain parameters read from the genesis file when a new ledger is created.
+#[derive(Deserialize, Default)]
+pub struct GenesisConfig {
+    #[serde(default)]
+    pub staking: StakeConfig,
+}
+
+/// Reads the genesis file, falling back to defaults when it is missing or malformed.
+pub fn load_genesis_config() -> GenesisConfig {
+    match fs::read(GENESIS_PATH).map(|bytes| serde_json::from_slice::<GenesisConfig>(&bytes)) {
+        Ok(Ok(config)) => config,
+        Ok(Err(e)) => {
+            log::error!("Local Alpha: Invalid genesis config {}: {}; using defaults", GENESIS_PATH, e);
+            GenesisConfig::default()
+        }
+        Err(_) => GenesisConfig::default(),
+    }
+}
+
+/// Writes the genesis file unless one already exists, so local edits survive restarts.
+pub fn ensure_genesis() {
+    if !Path::new(GENESIS_PATH).exists() {
+        generate_genesis();
+    }
+}
 
 pub fn generate_genesis() {
     let genesis_config = r#"
@@ -11,10 +41,22 @@ pub fn generate_genesis() {
   "halving_interval": 730000,
   "decimals": 9,
   "scarcity_note": "Limited supply: 700M XRS, 200M treasury, 500M via PoW halving (342.5 XRS/block) for $50M market cap by 2025",
-  "treasury_address": "XerisTrustWallet"
+  "treasury_address": "XerisTrustWallet",
+  "staking": {
+    "unbonding_epochs": 2,
+    "slash_bps": 500,
+    "jail_epochs": 2,
+    "max_stake_bps": 1000,
+    "inflation": {
+      "initial_bps": 800,
+      "terminal_bps": 150,
+      "taper_bps": 1500,
+      "epochs_per_year": 788400
+    }
+  }
 }
 "#;
-    let path = "./xrs-genesis.json".to_string();
+    let path = GENESIS_PATH.to_string();
 
     if let Some(parent) = Path::new(&path).parent() {
         fs::create_dir_all(parent).expect("Failed to create directory for genesis file");
@@ -24,4 +66,14 @@ pub fn generate_genesis() {
     file.write_all(genesis_config.as_bytes())
         .expect("Failed to write genesis config");
     println!("Genesis config created at {}. Capped supply: 700M XRS.", path);
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn shipped_genesis_uses_default_staking() {
+        let config: GenesisConfig = serde_json::from_str(include_str!("../xrs-genesis.json")).unwrap();
+        assert_eq!(config.staking, StakeConfig::default());
+    }
+}
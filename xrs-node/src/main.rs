@@ -2,15 +2,13 @@
 // XerisCoin Main Entry - Local Alpha Mode with --local-alpha Flag
 // Triple Consensus Node (PoH + PoW + PoS) - US Provisional #63/887,511
 
-use solana_sdk::{pubkey::Pubkey, signature::{Keypair, Signer, SignerError}};
-use std::error::Error;
+use solana_sdk::{pubkey::Pubkey, signature::{Keypair, Signer}, transaction::Transaction};
 use clap::{Command, Arg};
 use std::sync::{Arc, Mutex};
 use tokio::runtime::Runtime;
-use crate::network::Network;
+use tokio::task::{JoinError, JoinHandle};
 use log::{info, error, debug};
 use prometheus::{Gauge, Registry};
-use env_logger;  // For env_logger::init()
 
 mod pow;
 mod poh;
@@ -20,74 +18,219 @@ mod network;
 mod staking;
 mod explorer;
 mod tx_pool;
+mod pubsub;
+mod api;
+mod error;
+mod fees;
+mod banking;
+mod accounts;
+mod system_processor;
+mod state_tree;
+mod merkle;
+mod snapshot;
+mod store;
+mod miner;
 
-use crate::ledger::Ledger;
+use crate::ledger::{Block, Ledger, Retention, RetentionMode, DEFAULT_KEEP_BLOCKS};
+use crate::error::{ConsensusError, LedgerError};
+use crate::pubsub::{Notification, PubSub};
+use crate::tx_pool::{Mempool, MempoolStats, MAX_TXS_PER_BLOCK};
+use crate::pow::WorkServer;
+use crate::miner::MinerPool;
 
 struct Validator {
     keypair: Keypair,
     ledger: Arc<Mutex<Ledger>>, // Shared ledger
     poh_recorder: poh::PoHRecorder,
-    validators: Arc<Mutex<Vec<Pubkey>>>,
     is_bootstrap: bool,
-    tx_pool: Arc<Mutex<tx_pool::PriorityQueue>>,
-    network: Arc<Mutex<Network>>,
+    tx_pool: Arc<Mutex<Mempool>>,
     registry: Registry,
-    block_time_gauge: Gauge,
+    hashrate_gauge: Gauge,
+    mempool_gauges: MempoolGauges,
+    pubsub: PubSub,
+    work_server: Arc<WorkServer>,
+    external_miners: bool,
+    miner: Arc<MinerPool>,
+    /// The in-process miner's job for a leader slot, run on a blocking thread.
+    mining: Option<(u64, JoinHandle<Result<Block, ConsensusError>>)>,
+    /// Transactions from withdrawn work, carried into the next leader slot's work.
+    unmined: Vec<Transaction>,
+}
+
+struct MempoolGauges {
+    size: Gauge,
+    p50_fee: Gauge,
+    p90_fee: Gauge,
+    p99_fee: Gauge,
+}
+
+impl MempoolGauges {
+    fn register(registry: &Registry) -> Self {
+        let gauge = |name: &str, help: &str| {
+            let gauge = Gauge::new(name, help).expect("Failed to create gauge");
+            registry.register(Box::new(gauge.clone())).expect("Failed to register gauge");
+            gauge
+        };
+        MempoolGauges {
+            size: gauge("mempool_size", "Pending transactions in the mempool"),
+            p50_fee: gauge("mempool_fee_p50_lamports", "Median pending fee"),
+            p90_fee: gauge("mempool_fee_p90_lamports", "90th percentile pending fee"),
+            p99_fee: gauge("mempool_fee_p99_lamports", "99th percentile pending fee"),
+        }
+    }
+
+    fn update(&self, stats: &MempoolStats) {
+        self.size.set(stats.size as f64);
+        self.p50_fee.set(stats.p50_fee as f64);
+        self.p90_fee.set(stats.p90_fee as f64);
+        self.p99_fee.set(stats.p99_fee as f64);
+    }
 }
 
 impl Validator {
-    fn new(keypair: Keypair, ledger: Arc<Mutex<Ledger>>, is_bootstrap: bool) -> Self {
-        let tx_pool = Arc::new(Mutex::new(tx_pool::PriorityQueue::new()));
-        let validators = Arc::new(Mutex::new(vec![keypair.pubkey()]));
-        let network = Arc::new(Mutex::new(Network::new(tx_pool.clone(), validators.clone(), ledger.clone())));
+    fn new(
+        keypair: Keypair,
+        ledger: Arc<Mutex<Ledger>>,
+        is_bootstrap: bool,
+        pubsub: PubSub,
+        external_miners: bool,
+        miner_threads: usize,
+    ) -> Self {
+        let tx_pool = Arc::new(Mutex::new(Mempool::new()));
         let registry = Registry::new();
-        let block_time_gauge = Gauge::new("block_time_ms", "Time to produce a block").expect("Failed to create gauge");
-        registry.register(Box::new(block_time_gauge.clone())).expect("Failed to register gauge");
+        let hashrate_gauge = Gauge::new("miner_hashrate_hs", "In-process miner hashes per second").expect("Failed to create gauge");
+        registry.register(Box::new(hashrate_gauge.clone())).expect("Failed to register gauge");
+        let mempool_gauges = MempoolGauges::register(&registry);
+        let work_server = Arc::new(WorkServer::new(keypair.insecure_clone()));
         Validator {
             keypair,
             ledger,
             poh_recorder: poh::PoHRecorder::new(),
-            validators,
             is_bootstrap,
             tx_pool,
-            network,
             registry,
-            block_time_gauge,
+            hashrate_gauge,
+            mempool_gauges,
+            pubsub,
+            work_server,
+            external_miners,
+            miner: Arc::new(MinerPool::new(miner_threads)),
+            mining: None,
+            unmined: Vec::new(),
         }
     }
 
-    fn select_leader(&self) -> Pubkey {
-        debug!("Local Alpha: Selecting validator pubkey: {}", self.keypair.pubkey());
-        self.keypair.pubkey()
+    /// The epoch's scheduled leader for `slot`; without a schedule this node leads.
+    fn select_leader(&self, slot: u64) -> Pubkey {
+        let leader = self.ledger.lock().unwrap().slot_leader(slot).unwrap_or_else(|| self.keypair.pubkey());
+        debug!("Local Alpha: Scheduled leader for slot {}: {}", slot, leader);
+        leader
     }
 
-    async fn run(&mut self) -> Result<(), Box<dyn Error>> {
+    async fn run(&mut self) -> Result<(), ConsensusError> {
+        // Continue after the last restored block so new blocks are not rejected as stale
+        let last_slot = self.ledger.lock().unwrap().get_last_block().map(|b| b.slot).unwrap_or(0);
+        self.poh_recorder.set_slot(last_slot);
         self.poh_recorder.start()?;
         info!(
             "Local Alpha: XRS {} node started: {} (Patent Pending)",
             if self.is_bootstrap { "Bootstrap" } else { "Validator" },
             self.keypair.pubkey()
         );
+        if self.external_miners {
+            info!("Local Alpha: Leader slots left to external miners on /work and /submit_block");
+        } else {
+            info!("Local Alpha: Mining leader slots on {} threads", self.miner.threads());
+        }
 
         loop {
             let slot = self.poh_recorder.current_slot();
-            if self.keypair.pubkey() == self.select_leader() {
+            {
+                let ledger = self.ledger.lock().unwrap();
+                let expired = self.tx_pool.lock().unwrap().expire(|blockhash| ledger.is_blockhash_valid(blockhash));
+                if expired > 0 {
+                    info!("Local Alpha: Expired {} mempool transactions with aged-out blockhashes", expired);
+                }
+            }
+            // A new slot cancels work left open from an earlier one
+            if self.work_server.open_work().is_some_and(|work| work.slot < slot) {
+                if let Some(stale) = self.work_server.withdraw() {
+                    self.unmined.extend(stale.transactions);
+                }
+            }
+            if let Some((job_slot, job)) = self.mining.take() {
+                self.finish_mining(job_slot, job.await)?;
+            }
+            if self.keypair.pubkey() == self.select_leader(slot) {
                 debug!("Local Alpha: Validator selected as leader for slot {}", slot);
                 let poh_hash = self.poh_recorder.hash();
-                match pow::propose_block(slot, &self.keypair, &self.ledger, poh_hash) {
-                    Ok(block) => {
-                        self.ledger.lock().unwrap().add_block(block)?;
-                        info!("Local Alpha: Block proposed and added for slot {}", slot);
+                let prepared = pow::prepare_work(slot, &self.keypair.pubkey(), &self.ledger.lock().unwrap(), poh_hash);
+                match prepared {
+                    Ok(mut work) => {
+                        let room = MAX_TXS_PER_BLOCK.saturating_sub(self.unmined.len());
+                        work.transactions = std::mem::take(&mut self.unmined)
+                            .into_iter()
+                            .chain(self.tx_pool.lock().unwrap().take_batch(room).into_iter().map(|entry| entry.tx))
+                            .collect();
+                        self.work_server.publish(work.clone());
+                        if self.external_miners {
+                            debug!("Local Alpha: Work for slot {} open to external miners", slot);
+                        } else {
+                            let (work_server, miner) = (self.work_server.clone(), self.miner.clone());
+                            let job = tokio::task::spawn_blocking(move || pow::propose_block(&work, &work_server, &miner));
+                            self.mining = Some((slot, job));
+                        }
                     }
                     Err(e) => {
                         error!("Local Alpha: Failed to propose block for slot {}: {}", slot, e);
                     }
                 }
             }
+            self.mempool_gauges.update(&self.tx_pool.lock().unwrap().stats());
             self.poh_recorder.tick();
-            tokio::time::sleep(std::time::Duration::from_millis(400)).await;
+            self.pubsub.notify(Notification::Slot {
+                slot: self.poh_recorder.current_slot(),
+                parent: slot,
+            });
+            // Wait out the slot, adding the block as soon as the miner solves it
+            let tick = tokio::time::sleep(std::time::Duration::from_millis(400));
+            tokio::pin!(tick);
+            if let Some((job_slot, mut job)) = self.mining.take() {
+                tokio::select! {
+                    joined = &mut job => self.finish_mining(job_slot, joined)?,
+                    _ = &mut tick => self.mining = Some((job_slot, job)),
+                }
+            }
+            tick.await;
+            self.hashrate_gauge.set(self.miner.sample_hashrate());
         }
     }
+
+    /// Adds the block a finished mining job solved for `slot`. A rejected block's transactions
+    /// go back to the next leader slot.
+    fn finish_mining(&mut self, slot: u64, joined: Result<Result<Block, ConsensusError>, JoinError>) -> Result<(), ConsensusError> {
+        match joined {
+            Ok(Ok(block)) => {
+                let transactions = block.transactions.clone();
+                match self.ledger.lock().unwrap().produce_block(block, &self.keypair) {
+                    Ok(_) => info!("Local Alpha: Block proposed and added for slot {}", slot),
+                    Err(e @ (LedgerError::StaleSlot { .. }
+                    | LedgerError::MaliciousBlock { .. }
+                    | LedgerError::ValidatorJailed { .. })) => {
+                        error!("Local Alpha: Proposed block for slot {} rejected: {}", slot, e);
+                        self.unmined.extend(transactions);
+                    }
+                    Err(e) => return Err(e.into()),
+                }
+            }
+            Ok(Err(ConsensusError::StaleWork { .. })) => {
+                info!("Local Alpha: Mining for slot {} cancelled: the slot ended or an external miner solved it", slot);
+            }
+            Ok(Err(e)) => error!("Local Alpha: Failed to propose block for slot {}: {}", slot, e),
+            Err(e) => error!("Local Alpha: Miner for slot {} failed: {}", slot, e),
+        }
+        Ok(())
+    }
 }
 
 fn main() {
@@ -112,8 +255,149 @@ fn main() {
         )
         .arg(Arg::new("local-alpha").long("local-alpha").action(clap::ArgAction::SetTrue)
             .help("Run local-only alpha: isolated on 127.0.0.1, temp keys, genesis init"))
+        .arg(
+            Arg::new("snapshot-from")
+                .long("snapshot-from")
+                .value_name("host:port")
+                .value_parser(clap::value_parser!(String))
+                .help("Bootstrap a new local alpha ledger from a peer's /snapshot before starting"),
+        )
+        .arg(Arg::new("external-miners").long("external-miners").action(clap::ArgAction::SetTrue)
+            .help("Leave leader-slot work to external miners on /work and /submit_block instead of mining in-process"))
+        .arg(
+            Arg::new("miner-threads")
+                .long("miner-threads")
+                .value_name("threads")
+                .value_parser(clap::value_parser!(usize))
+                .help("Threads for the in-process miner (default: one per core)"),
+        )
+        .arg(
+            Arg::new("snapshot-interval")
+                .long("snapshot-interval")
+                .value_name("slots")
+                .value_parser(clap::value_parser!(u64))
+                .help("Write a ledger snapshot every N slots (default 1000)"),
+        )
+        .arg(
+            Arg::new("ledger-mode")
+                .long("ledger-mode")
+                .value_name("mode")
+                .value_parser(["pruned", "archival"])
+                .default_value("archival")
+                .help("pruned: keep only recent blocks plus snapshots on disk; archival: keep every block on disk, loaded on demand"),
+        )
+        .arg(
+            Arg::new("keep-blocks")
+                .long("keep-blocks")
+                .value_name("num_blocks")
+                .value_parser(clap::value_parser!(usize))
+                .help("Blocks held in memory (default 1000)"),
+        )
+        .arg(
+            Arg::new("bench-banking")
+                .long("bench-banking")
+                .value_name("num_txs")
+                .value_parser(clap::value_parser!(usize))
+                .help("Benchmark serial vs parallel banking stage on num_txs independent transfers"),
+        )
+        .arg(
+            Arg::new("stress-test")
+                .long("stress-test")
+                .value_name("num_txs")
+                .value_parser(clap::value_parser!(usize))
+                .help("Benchmark the append-only transaction Merkle accumulator with num_txs leaves"),
+        )
+        .arg(
+            Arg::new("verify-account-proof")
+                .long("verify-account-proof")
+                .value_name("proof_json")
+                .value_parser(clap::value_parser!(String))
+                .help("Verify an /account_proof response offline against its state root (light client check)"),
+        )
+        .arg(
+            Arg::new("verify-tx-proof")
+                .long("verify-tx-proof")
+                .value_name("proof_json")
+                .value_parser(clap::value_parser!(String))
+                .help("Verify a /tx_proof receipt offline against its block tx root"),
+        )
         .get_matches();
 
+    let retention = Retention {
+        mode: match matches.get_one::<String>("ledger-mode").map(String::as_str) {
+            Some("pruned") => RetentionMode::Pruned,
+            _ => RetentionMode::Archival,
+        },
+        keep_blocks: matches.get_one::<usize>("keep-blocks").copied().unwrap_or(DEFAULT_KEEP_BLOCKS),
+    };
+
+    if let Some(&num_txs) = matches.get_one::<usize>("stress-test") {
+        let path = std::env::temp_dir().join(format!("xrs-stress-{}.dat", std::process::id())).to_string_lossy().to_string();
+        if let Err(e) = Ledger::in_memory(path).and_then(|mut ledger| ledger.stress_test(num_txs)) {
+            error!("Local Alpha: Stress test failed: {}", e);
+        }
+        return;
+    }
+
+    if let Some(path) = matches.get_one::<String>("verify-tx-proof") {
+        let proof = std::fs::read_to_string(path)
+            .map_err(|e| e.to_string())
+            .and_then(|json| serde_json::from_str::<serde_json::Value>(&json).map_err(|e| e.to_string()))
+            .and_then(|value| {
+                let proof = value.get("data").cloned().unwrap_or(value);
+                serde_json::from_value::<merkle::TxProof>(proof).map_err(|e| e.to_string())
+            });
+        match proof {
+            Ok(proof) if merkle::verify_tx_proof(&proof) => {
+                println!("Receipt valid: {} included at slot {} index {} (tx root {})", proof.signature, proof.slot, proof.index, proof.tx_root);
+            }
+            Ok(proof) => {
+                println!("Receipt INVALID for {} at slot {}", proof.signature, proof.slot);
+                std::process::exit(1);
+            }
+            Err(e) => {
+                error!("Local Alpha: Failed to read receipt {}: {}", path, e);
+                std::process::exit(1);
+            }
+        }
+        return;
+    }
+
+    if let Some(path) = matches.get_one::<String>("verify-account-proof") {
+        let proof = std::fs::read_to_string(path)
+            .map_err(|e| e.to_string())
+            .and_then(|json| serde_json::from_str::<serde_json::Value>(&json).map_err(|e| e.to_string()))
+            .and_then(|value| {
+                // Accept the full API response or just its data payload
+                let proof = value.get("data").cloned().unwrap_or(value);
+                serde_json::from_value::<state_tree::AccountProof>(proof).map_err(|e| e.to_string())
+            });
+        match proof {
+            Ok(proof) if state_tree::verify_account_proof(&proof) => {
+                println!("Proof valid: {} at slot {} (state root {})", proof.pubkey, proof.slot, proof.state_root);
+            }
+            Ok(proof) => {
+                println!("Proof INVALID for {} at slot {}", proof.pubkey, proof.slot);
+                std::process::exit(1);
+            }
+            Err(e) => {
+                error!("Local Alpha: Failed to read proof {}: {}", path, e);
+                std::process::exit(1);
+            }
+        }
+        return;
+    }
+
+    if let Some(&num_txs) = matches.get_one::<usize>("bench-banking") {
+        if let Err(e) = banking::run_benchmark(num_txs, banking::BankingStage::default_threads()) {
+            error!("Local Alpha: Banking benchmark failed: {}", e);
+        }
+        return;
+    }
+
+    let external_miners = matches.get_flag("external-miners");
+    let miner_threads = matches.get_one::<usize>("miner-threads").copied().unwrap_or_else(MinerPool::default_threads);
+
     if matches.get_flag("genesis") {
         genesis::generate_genesis();
         return;
@@ -122,27 +406,61 @@ fn main() {
     // Local Alpha Mode: Auto-init everything isolated
     if matches.get_flag("local-alpha") {
         info!("Local Alpha v0.1.0 Starting - Patent Pending © 2025 Xeris (Triple Consensus)");
-        genesis::generate_genesis(); // Local genesis
+        genesis::ensure_genesis(); // Local genesis, kept across restarts
         let ledger_path = "local-ledger.dat".to_string();
-        let mut ledger_inner = Ledger::new(ledger_path);  // Mutable for auto-stake
+        if let Some(peer) = matches.get_one::<String>("snapshot-from") {
+            if let Err(e) = Ledger::bootstrap_from_peer(&ledger_path, peer) {
+                error!("Local Alpha: Snapshot bootstrap from {} failed: {}", peer, e);
+            }
+        }
+        let mut ledger_inner = match Ledger::open(ledger_path.clone(), retention) {  // Mutable for auto-stake
+            Ok(ledger) => ledger,
+            Err(e) => {
+                error!("Local Alpha: Failed to open ledger {}: {}", ledger_path, e);
+                return;
+            }
+        };
+        if let Some(&slots) = matches.get_one::<u64>("snapshot-interval") {
+            ledger_inner.set_snapshot_interval(slots);
+        }
         let keypair = Keypair::new(); // Temp local keypair (no file load)
         info!("Local Alpha: Temp keypair generated: {}", keypair.pubkey());
 
-        // FIX: Auto-airdrop/stake 1000 XRS to temp keypair for proposing
-        let stake_amount = 1_000_000_000_000u64;  // 1000 XRS in lamports
-        if let Err(e) = ledger_inner.airdrop(&keypair.pubkey().to_string(), stake_amount) {
+        // Auto-airdrop 1000 XRS (+1 XRS for rent and fees), stake it through the stake program
+        // and register as a validator so the node joins the active set
+        let stake_amount = staking::MIN_STAKE;  // 1000 XRS in lamports
+        let staked = ledger_inner.airdrop(&keypair.pubkey().to_string(), stake_amount + 1_000_000_000).and_then(|()| {
+            let tx = Transaction::new_signed_with_payer(
+                &[
+                    staking::stake(&keypair.pubkey(), stake_amount),
+                    staking::register_validator(&keypair.pubkey(), &keypair.pubkey(), "127.0.0.1:4000", staking::DEFAULT_COMMISSION),
+                ],
+                Some(&keypair.pubkey()),
+                &[&keypair],
+                ledger_inner.latest_blockhash(),
+            );
+            let slot = ledger_inner.get_last_block().map(|b| b.slot).unwrap_or(0);
+            ledger_inner.add_transaction(tx, slot)?;
+            ledger_inner.activate_bootstrap_stake(&keypair.pubkey())
+        });
+        if let Err(e) = staked {
             error!("Local Alpha: Auto-stake failed: {}", e);
         } else {
             info!("Local Alpha: Auto-staked 1000 XRS to validator: {}", keypair.pubkey());
         }
+        let pubsub = PubSub::new();
+        ledger_inner.set_pubsub(pubsub.clone());
         let ledger = Arc::new(Mutex::new(ledger_inner));  // Now wrap
 
-        let mut validator = Validator::new(keypair, ledger.clone(), true); // Bootstrap local
+        let mut validator = Validator::new(keypair, ledger.clone(), true, pubsub.clone(), external_miners, miner_threads); // Bootstrap local
+        let tx_pool = validator.tx_pool.clone();
+        let registry = validator.registry.clone();
+        let work_server = validator.work_server.clone();
         let rt = Runtime::new().unwrap();
         rt.block_on(async {
             if let Err(e) = tokio::try_join!(
-                async { network::start_network(ledger.clone()).await; Ok(()) },
-                async { explorer::start_explorer(ledger.clone()).await; Ok(()) },
+                async { network::start_network(ledger.clone(), tx_pool.clone(), work_server.clone()).await; Ok(()) },
+                async { let _ = explorer::start_explorer(ledger.clone(), pubsub.clone(), registry.clone()).await; Ok(()) },
                 validator.run()
             ) {
                 error!("Local Alpha: Failed to start: {}", e);
@@ -160,8 +478,21 @@ fn main() {
         }
     };
     rt.block_on(async {
-        let ledger = Arc::new(Mutex::new(Ledger::new("ledger.dat".to_string())));
-        let ledger_clone = ledger.clone();
+        let pubsub = PubSub::new();
+        let open_ledger = |path: &str| {
+            let mut ledger_inner = match Ledger::open(path.to_string(), retention) {
+                Ok(ledger) => ledger,
+                Err(e) => {
+                    error!("Failed to open ledger {}: {}", path, e);
+                    return None;
+                }
+            };
+            if let Some(&slots) = matches.get_one::<u64>("snapshot-interval") {
+                ledger_inner.set_snapshot_interval(slots);
+            }
+            ledger_inner.set_pubsub(pubsub.clone());
+            Some(Arc::new(Mutex::new(ledger_inner)))
+        };
         debug!("Starting network, explorer, and validator");
         if let Some(values) = matches.get_many::<String>("bootstrap") {
             let parts: Vec<String> = values.cloned().collect();
@@ -190,7 +521,7 @@ fn main() {
                 error!("Invalid keypair length: expected 64 bytes, got {}", keypair_array.len());
                 return;
             }
-            let keypair = match Keypair::from_bytes(&keypair_array) {  // FIXED: Use from_bytes(&Vec<u8>) -> & [u8]
+            let keypair = match Keypair::try_from(keypair_array.as_slice()) {
                 Ok(kp) => kp,
                 Err(e) => {
                     error!("Failed to create keypair from bytes: {}", e);
@@ -198,10 +529,16 @@ fn main() {
                 }
             };
             debug!("Starting bootstrap validator with ledger {} and pubkey {}", ledger_path, keypair.pubkey());
-            let mut validator = Validator::new(keypair, ledger_clone.clone(), true);
+            let Some(ledger) = open_ledger(&ledger_path) else {
+                return;
+            };
+            let mut validator = Validator::new(keypair, ledger.clone(), true, pubsub.clone(), external_miners, miner_threads);
+            let tx_pool = validator.tx_pool.clone();
+            let registry = validator.registry.clone();
+            let work_server = validator.work_server.clone();
             if let Err(e) = tokio::try_join!(
-                async { network::start_network(ledger_clone.clone()).await; Ok(()) },
-                async { explorer::start_explorer(ledger_clone.clone()).await; Ok(()) },
+                async { network::start_network(ledger.clone(), tx_pool.clone(), work_server.clone()).await; Ok(()) },
+                async { let _ = explorer::start_explorer(ledger.clone(), pubsub.clone(), registry.clone()).await; Ok(()) },
                 validator.run()
             ) {
                 error!("Bootstrap failed: {}", e);
@@ -234,7 +571,7 @@ fn main() {
                 error!("Invalid keypair length: expected 64 bytes, got {}", keypair_array.len());
                 return;
             }
-            let keypair = match Keypair::from_bytes(&keypair_array) {  // FIXED: Use from_bytes(&Vec<u8>) -> & [u8]
+            let keypair = match Keypair::try_from(keypair_array.as_slice()) {
                 Ok(kp) => kp,
                 Err(e) => {
                     error!("Failed to create keypair from bytes: {}", e);
@@ -242,10 +579,21 @@ fn main() {
                 }
             };
             debug!("Starting validator with ledger {} and bootstrap IP {}", ledger_path, bootstrap_ip);
-            let mut validator = Validator::new(keypair, ledger_clone.clone(), false);
+            // New validators fast-boot from the bootstrap node's newest snapshot
+            let peer = if bootstrap_ip.contains(':') { bootstrap_ip.clone() } else { format!("{}:4001", bootstrap_ip) };
+            if let Err(e) = Ledger::bootstrap_from_peer(&ledger_path, &peer) {
+                error!("Snapshot bootstrap from {} failed, booting from local ledger: {}", peer, e);
+            }
+            let Some(ledger) = open_ledger(&ledger_path) else {
+                return;
+            };
+            let mut validator = Validator::new(keypair, ledger.clone(), false, pubsub.clone(), external_miners, miner_threads);
+            let tx_pool = validator.tx_pool.clone();
+            let registry = validator.registry.clone();
+            let work_server = validator.work_server.clone();
             if let Err(e) = tokio::try_join!(
-                async { network::start_network(ledger_clone.clone()).await; Ok(()) },
-                async { explorer::start_explorer(ledger_clone.clone()).await; Ok(()) },
+                async { network::start_network(ledger.clone(), tx_pool.clone(), work_server.clone()).await; Ok(()) },
+                async { let _ = explorer::start_explorer(ledger.clone(), pubsub.clone(), registry.clone()).await; Ok(()) },
                 validator.run()
             ) {
                 error!("Validator failed to connect to {}: {}", bootstrap_ip, e);
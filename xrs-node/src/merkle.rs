@@ -0,0 +1,217 @@
+// Patent Pending Copyright © 2025 Xeris Web Co. All rights reserved.
+// XerisCoin Transaction Merkle Trees - Per-Block TX Roots, Receipts & Append-Only Accumulator (Local Alpha)
+// rs_merkle layout: sha256 leaves, odd nodes promoted unchanged to the next layer
+
+use solana_sdk::{hash::Hash, signature::Signature, transaction::Transaction};
+use rs_merkle::{Hasher, MerkleTree, algorithms::Sha256};
+use serde::{Serialize, Deserialize};
+
+pub fn tx_leaf(signature: &Signature) -> [u8; 32] {
+    Sha256::hash(signature.as_ref())
+}
+
+/// Merkle root over a block's transaction signatures, in block order; default hash for an empty block.
+pub fn block_tx_root(transactions: &[Transaction]) -> Hash {
+    let leaves: Vec<[u8; 32]> = transactions.iter().map(|tx| tx_leaf(&tx.signatures[0])).collect();
+    MerkleTree::<Sha256>::from_leaves(&leaves)
+        .root()
+        .map(Hash::new_from_array)
+        .unwrap_or_default()
+}
+
+/// Receipt that a transaction was included in the block at `slot`.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct TxProof {
+    pub signature: String,
+    pub slot: u64,
+    pub index: usize,
+    pub total: usize,
+    pub leaf: String,
+    pub tx_root: String,
+    /// Sibling hashes from the leaf layer upward, hex-encoded; layers where the node is promoted have none.
+    pub proof: Vec<String>,
+}
+
+/// Builds the inclusion proof for the transaction at `index` of a block.
+pub fn prove_tx(transactions: &[Transaction], index: usize, slot: u64) -> Option<TxProof> {
+    let tx = transactions.get(index)?;
+    let leaves: Vec<[u8; 32]> = transactions.iter().map(|tx| tx_leaf(&tx.signatures[0])).collect();
+    let tree = MerkleTree::<Sha256>::from_leaves(&leaves);
+    Some(TxProof {
+        signature: tx.signatures[0].to_string(),
+        slot,
+        index,
+        total: leaves.len(),
+        leaf: hex::encode(leaves[index]),
+        tx_root: hex::encode(tree.root()?),
+        proof: tree.proof(&[index]).proof_hashes().iter().map(hex::encode).collect(),
+    })
+}
+
+fn decode_hash(hex_str: &str) -> Option<[u8; 32]> {
+    hex::decode(hex_str).ok()?.try_into().ok()
+}
+
+fn concat_and_hash(left: &[u8; 32], right: &[u8; 32]) -> [u8; 32] {
+    let mut bytes = [0u8; 64];
+    bytes[..32].copy_from_slice(left);
+    bytes[32..].copy_from_slice(right);
+    Sha256::hash(&bytes)
+}
+
+/// Checks a [`TxProof`] against its `tx_root`; the leaf must be sha256 of the signature.
+pub fn verify_tx_proof(proof: &TxProof) -> bool {
+    let (Ok(signature), Some(root), Some(leaf)) = (
+        proof.signature.parse::<Signature>(),
+        decode_hash(&proof.tx_root),
+        decode_hash(&proof.leaf),
+    ) else {
+        return false;
+    };
+    if leaf != tx_leaf(&signature) || proof.index >= proof.total {
+        return false;
+    }
+    let mut siblings = proof.proof.iter();
+    let mut hash = leaf;
+    let mut index = proof.index;
+    let mut width = proof.total;
+    while width > 1 {
+        // The last node of an odd layer has no sibling and moves up unchanged
+        if index % 2 == 1 || index + 1 < width {
+            let Some(sibling) = siblings.next().and_then(|s| decode_hash(s)) else {
+                return false;
+            };
+            hash = if index % 2 == 1 { concat_and_hash(&sibling, &hash) } else { concat_and_hash(&hash, &sibling) };
+        }
+        index /= 2;
+        width = width.div_ceil(2);
+    }
+    siblings.next().is_none() && hash == root
+}
+
+/// Append-only Merkle accumulator over every transaction the ledger has committed.
+///
+/// Keeps only the roots of the perfect subtrees ("peaks") covering the leaves so far, like a
+/// binary counter: an append merges equal-sized peaks in O(log n), and memory stays O(log n).
+/// Roots match `MerkleTree::<Sha256>::from_leaves` over the same leaves, so they are stable
+/// whether computed incrementally or by a full rebuild.
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct MerkleAccumulator {
+    // (height, root) from the largest subtree to the smallest; heights strictly decrease
+    peaks: Vec<(u32, [u8; 32])>,
+    leaf_count: u64,
+}
+
+impl MerkleAccumulator {
+    pub fn new() -> Self {
+        MerkleAccumulator::default()
+    }
+
+    pub fn append(&mut self, leaf: [u8; 32]) {
+        let mut height = 0;
+        let mut hash = leaf;
+        while let Some(&(peak_height, peak)) = self.peaks.last() {
+            if peak_height != height {
+                break;
+            }
+            self.peaks.pop();
+            hash = concat_and_hash(&peak, &hash);
+            height += 1;
+        }
+        self.peaks.push((height, hash));
+        self.leaf_count += 1;
+    }
+
+    pub fn leaf_count(&self) -> u64 {
+        self.leaf_count
+    }
+
+    /// Root over all leaves appended so far; `None` while empty. Folds peaks from the
+    /// smallest upward, matching how rs_merkle promotes a trailing odd node.
+    pub fn root(&self) -> Option<[u8; 32]> {
+        let mut peaks = self.peaks.iter().rev();
+        let (_, mut root) = *peaks.next()?;
+        for (_, peak) in peaks {
+            root = concat_and_hash(peak, &root);
+        }
+        Some(root)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn signed(count: usize) -> Vec<Transaction> {
+        (0..count)
+            .map(|_| Transaction { signatures: vec![Signature::new_unique()], ..Transaction::default() })
+            .collect()
+    }
+
+    #[test]
+    fn every_transaction_proves_against_the_block_root() {
+        // Odd and even widths, so promoted nodes appear at different layers
+        for count in 1..=9 {
+            let transactions = signed(count);
+            let root = hex::encode(block_tx_root(&transactions));
+            for index in 0..count {
+                let proof = prove_tx(&transactions, index, 7).unwrap();
+                assert_eq!(proof.tx_root, root);
+                assert!(verify_tx_proof(&proof), "{} of {}", index, count);
+            }
+        }
+        assert!(prove_tx(&signed(3), 3, 7).is_none());
+        assert_eq!(block_tx_root(&[]), Hash::default());
+    }
+
+    #[test]
+    fn tampered_tx_proofs_fail() {
+        let transactions = signed(5);
+        let proof = prove_tx(&transactions, 2, 7).unwrap();
+        let other = prove_tx(&transactions, 3, 7).unwrap();
+
+        let mut forged = proof.clone();
+        forged.signature = other.signature.clone();
+        assert!(!verify_tx_proof(&forged));
+        forged.leaf = other.leaf.clone();
+        assert!(!verify_tx_proof(&forged));
+
+        let mut forged = proof.clone();
+        forged.index = 3;
+        assert!(!verify_tx_proof(&forged));
+
+        let mut forged = proof.clone();
+        forged.proof.push(forged.proof[0].clone());
+        assert!(!verify_tx_proof(&forged));
+
+        let mut forged = proof;
+        forged.tx_root = hex::encode(block_tx_root(&signed(5)));
+        assert!(!verify_tx_proof(&forged));
+    }
+
+    #[test]
+    fn accumulator_root_matches_full_rebuild() {
+        let mut accumulator = MerkleAccumulator::new();
+        assert_eq!(accumulator.root(), None);
+        let mut leaves = Vec::new();
+        // Every size up to a few full powers of two, so each odd-peak shape is covered
+        for i in 0u32..600 {
+            let leaf = Sha256::hash(&i.to_le_bytes());
+            leaves.push(leaf);
+            accumulator.append(leaf);
+            assert_eq!(accumulator.leaf_count(), leaves.len() as u64);
+            assert_eq!(accumulator.root(), MerkleTree::<Sha256>::from_leaves(&leaves).root(), "{} leaves", leaves.len());
+        }
+    }
+
+    #[test]
+    fn accumulator_root_survives_serialization() {
+        let mut accumulator = MerkleAccumulator::new();
+        for i in 0u32..37 {
+            accumulator.append(Sha256::hash(&i.to_le_bytes()));
+        }
+        let restored: MerkleAccumulator = bincode::deserialize(&bincode::serialize(&accumulator).unwrap()).unwrap();
+        assert_eq!(restored.root(), accumulator.root());
+        assert_eq!(restored.leaf_count(), 37);
+    }
+}
@@ -0,0 +1,116 @@
+// Patent Pending Copyright © 2025 Xeris Web Co. All rights reserved.
+// XerisCoin CPU Miner - Multi-threaded scrypt Nonce Search (Local Alpha)
+// Shared by the validator's in-process miner and the standalone xrs-miner binary
+
+use rand::Rng;
+use scrypt::{scrypt, Params};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::Instant;
+
+/// scrypt (N=1024, r=1, p=1, 32 bytes) over `header` followed by `nonce` as a big-endian u64.
+pub fn scrypt_hash(header: &[u8], nonce: u64) -> Vec<u8> {
+    let mut input = header.to_vec();
+    input.extend_from_slice(&nonce.to_be_bytes());
+    let mut hash = vec![0u8; 32];
+    let params = Params::new(10, 1, 1).expect("Invalid scrypt params");
+    scrypt(&input, &[], &params, &mut hash).expect("Invalid scrypt output length");
+    hash
+}
+
+/// A fixed number of mining threads plus a running hash count for the hashrate gauge.
+pub struct MinerPool {
+    threads: usize,
+    hashes: AtomicU64,
+    /// When the hashrate was last sampled, and the hash count at that time.
+    sampled: Mutex<(Instant, u64)>,
+}
+
+impl MinerPool {
+    pub fn new(threads: usize) -> Self {
+        MinerPool {
+            threads: threads.max(1),
+            hashes: AtomicU64::new(0),
+            sampled: Mutex::new((Instant::now(), 0)),
+        }
+    }
+
+    /// One thread per available core.
+    pub fn default_threads() -> usize {
+        std::thread::available_parallelism().map(|n| n.get()).unwrap_or(1)
+    }
+
+    pub fn threads(&self) -> usize {
+        self.threads
+    }
+
+    /// Searches nonces on every thread from a random start, thread `i` trying `start + i`,
+    /// `start + i + threads`, ..., until one hashes below `target`. Returns the nonce and its
+    /// hash, or `None` once `cancelled` returns true; each thread checks it before every hash.
+    pub fn solve<F>(&self, header: &[u8], target: &[u8], cancelled: F) -> Option<(u64, Vec<u8>)>
+    where
+        F: Fn() -> bool + Sync,
+    {
+        let start = rand::thread_rng().gen::<u64>();
+        let done = AtomicBool::new(false);
+        let found = Mutex::new(None);
+        let (done_ref, found_ref, cancelled) = (&done, &found, &cancelled);
+        std::thread::scope(|scope| {
+            for offset in 0..self.threads as u64 {
+                scope.spawn(move || {
+                    let mut nonce = start.wrapping_add(offset);
+                    while !done_ref.load(Ordering::Relaxed) {
+                        if cancelled() {
+                            done_ref.store(true, Ordering::Relaxed);
+                            break;
+                        }
+                        let hash = scrypt_hash(header, nonce);
+                        self.hashes.fetch_add(1, Ordering::Relaxed);
+                        if hash.as_slice() < target {
+                            if !done_ref.swap(true, Ordering::Relaxed) {
+                                *found_ref.lock().unwrap() = Some((nonce, hash));
+                            }
+                            break;
+                        }
+                        nonce = nonce.wrapping_add(self.threads as u64);
+                    }
+                });
+            }
+        });
+        found.into_inner().unwrap()
+    }
+
+    /// Hashes per second since the previous sample.
+    pub fn sample_hashrate(&self) -> f64 {
+        let mut sampled = self.sampled.lock().unwrap();
+        let hashes = self.hashes.load(Ordering::Relaxed);
+        let elapsed = sampled.0.elapsed().as_secs_f64();
+        let rate = if elapsed > 0.0 { (hashes - sampled.1) as f64 / elapsed } else { 0.0 };
+        *sampled = (Instant::now(), hashes);
+        rate
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn solve_finds_a_nonce_below_target() {
+        let pool = MinerPool::new(2);
+        // One leading zero byte: roughly one hash in 256 qualifies
+        let mut target = vec![0xff; 32];
+        target[0] = 0;
+        let (nonce, hash) = pool.solve(b"header", &target, || false).expect("easy target is solvable");
+        assert_eq!(hash, scrypt_hash(b"header", nonce));
+        assert!(hash.as_slice() < target.as_slice());
+        assert!(pool.hashes.load(Ordering::Relaxed) > 0);
+    }
+
+    #[test]
+    fn cancelled_search_gives_up() {
+        let pool = MinerPool::new(2);
+        // No hash is below an all-zero target, so only cancellation ends the search
+        assert!(pool.solve(b"header", &[0; 32], || true).is_none());
+    }
+}
@@ -6,18 +6,23 @@ use tokio::net::TcpSocket;
 use tokio::io::{AsyncReadExt, AsyncWriteExt};
 use tokio::sync::Semaphore;
 use std::sync::{Arc, Mutex};
-use solana_sdk::{transaction::Transaction, signature::Signature, pubkey::Pubkey};
+use solana_sdk::{transaction::Transaction, message::Message, signature::Signature, pubkey::Pubkey};
 use serde::{Serialize, Deserialize};
 use std::collections::HashMap;
 use sha2::{Sha256, Digest};
 use log::{info, error, debug};
-use warp::Filter;
-use crate::ledger::Ledger;
-use crate::tx_pool::PrioritizedTx;
-use base64;
+use warp::{Filter, Reply};
+use crate::ledger::{Ledger, MAX_RECENT_BLOCKHASHES};
+use crate::tx_pool::Mempool;
+use crate::error::{LedgerError, MempoolError};
+use crate::api::{self, ApiResponse, ErrorCode};
+use crate::fees;
+use crate::accounts;
+use crate::snapshot;
+use crate::staking;
+use crate::pow::WorkServer;
 use std::time::Instant;
 use std::net::SocketAddr;
-use bincode;
 
 #[derive(Serialize, Deserialize)]
 pub enum NetworkMessage {
@@ -31,9 +36,19 @@ struct SubmitTransactionRequest {
     tx: String, // Base64-encoded transaction
 }
 
+#[derive(Serialize, Deserialize)]
+struct SubmitBlockRequest {
+    slot: u64,
+    nonce: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct FeeForMessageRequest {
+    message: String, // Base64-encoded message
+}
+
 pub struct Network {
-    tx_pool: Arc<Mutex<crate::tx_pool::PriorityQueue>>,
-    validators: Arc<Mutex<Vec<Pubkey>>>,
+    tx_pool: Arc<Mutex<Mempool>>,
     #[allow(dead_code)]
     whitelisted_ips: HashMap<String, bool>,
     connections_per_ip: HashMap<String, u32>,
@@ -44,18 +59,13 @@ pub struct Network {
 }
 
 impl Network {
-    pub fn new(
-        tx_pool: Arc<Mutex<crate::tx_pool::PriorityQueue>>,
-        validators: Arc<Mutex<Vec<Pubkey>>>,
-        ledger: Arc<Mutex<Ledger>>,
-    ) -> Self {
+    pub fn new(tx_pool: Arc<Mutex<Mempool>>, ledger: Arc<Mutex<Ledger>>) -> Self {
         let mut whitelisted_ips = HashMap::new();
         // Local Alpha: Whitelist only localhost (override original LAN)
         whitelisted_ips.insert("127.0.0.1".to_string(), true);
         whitelisted_ips.insert("::1".to_string(), true); // IPv6 localhost
         Network {
             tx_pool,
-            validators,
             whitelisted_ips,
             connections_per_ip: HashMap::new(),
             authenticated_nodes: HashMap::new(),
@@ -64,18 +74,22 @@ impl Network {
         }
     }
 
-    pub fn broadcast_transaction(&mut self, tx: &Transaction) {
-        // Local Alpha: Stubbed to local queue only (no external broadcast)
+    pub fn broadcast_transaction(&mut self, tx: &Transaction) -> Result<(), MempoolError> {
+        // Local Alpha: Stubbed to local mempool only (no external broadcast)
+        let fee = fees::calculate_fee(&tx.message).total();
         let mut tx_pool = self.tx_pool.lock().unwrap();
-        if tx_pool.len() < 10_000 {
-            let fee = (10.0 * 1_000_000_000.0 * 0.001) as u64;
-            tx_pool.push(PrioritizedTx {
-                tx: tx.clone(),
-                fee,
-            });
-            info!("Local Alpha: Gulf Stream TX forwarded locally {:?}", tx.signatures[0]);
-        } else {
-            info!("Local Alpha: TX pool full, dropping {:?}", tx.signatures[0]);
+        match tx_pool.insert(tx.clone(), fee) {
+            Ok(evicted) => {
+                if let Some(evicted) = evicted {
+                    info!("Local Alpha: Mempool evicted lower-fee tx {}", evicted);
+                }
+                info!("Local Alpha: Gulf Stream TX forwarded locally {:?} (fee {} lamports)", tx.signatures[0], fee);
+                Ok(())
+            }
+            Err(e) => {
+                info!("Local Alpha: Mempool rejected {:?}: {}", tx.signatures.first(), e);
+                Err(e)
+            }
         }
     }
 
@@ -112,13 +126,23 @@ impl Network {
         }
     }
 
-    pub fn authenticate_node(&mut self, node_id: &str, _signature: &Signature, _pubkey: &Pubkey) -> bool {
+    /// Admits a node whose id is an active-set validator identity that signed it.
+    pub fn authenticate_node(&mut self, node_id: &str, signature: &Signature) -> bool {
+        let Ok(pubkey) = Pubkey::try_from(node_id) else { return false };
+        if !self.ledger.lock().unwrap().get_active_validators().contains(&pubkey) {
+            info!("Local Alpha: Auth refused: {} is not in the active validator set", node_id);
+            return false;
+        }
+        if !signature.verify(pubkey.as_ref(), node_id.as_bytes()) {
+            info!("Local Alpha: Auth refused: bad signature from {}", node_id);
+            return false;
+        }
         self.authenticated_nodes.insert(node_id.to_string(), true);
         true
     }
 }
 
-pub async fn start_network(ledger: Arc<Mutex<Ledger>>) {
+pub async fn start_network(ledger: Arc<Mutex<Ledger>>, tx_pool: Arc<Mutex<Mempool>>, work_server: Arc<WorkServer>) {
     // Local Alpha: Bind to 127.0.0.1 only (override original 0.0.0.0)
     let tcp_addr: SocketAddr = "127.0.0.1:4000".parse().expect("Invalid TCP address");
     let http_addr: SocketAddr = "127.0.0.1:4001".parse().expect("Invalid HTTP address");
@@ -150,78 +174,432 @@ pub async fn start_network(ledger: Arc<Mutex<Ledger>>) {
     };
     debug!("Listening on TCP socket with backlog 100");
     let semaphore = Arc::new(Semaphore::new(100));
-    let network = Arc::new(Mutex::new(Network::new(
-        Arc::new(Mutex::new(crate::tx_pool::PriorityQueue::new())),
-        Arc::new(Mutex::new(vec![Pubkey::new_unique()])),
-        ledger.clone(),
-    )));
+    let network = Arc::new(Mutex::new(Network::new(tx_pool.clone(), ledger.clone())));
     let ledger_airdrop = ledger.clone();
     let network_submit = network.clone();
     let ledger_submit = ledger.clone();
+    let ledger_blockhash = ledger.clone();
+    let ledger_account = ledger.clone();
+    let ledger_proof = ledger.clone();
+    let ledger_tx_proof = ledger.clone();
+    let ledger_snapshot = ledger.clone();
+    let ledger_block = ledger.clone();
+    let ledger_stake = ledger.clone();
+    let ledger_validator = ledger.clone();
+    let ledger_rewards = ledger.clone();
+    let ledger_account_rewards = ledger.clone();
+    let tx_pool_stats = tx_pool.clone();
 
-    // Local Alpha: Add /work for miner
+    // Local Alpha: /work and /submit_block let external miners solve the validator's leader slots
+    let work_open = work_server.clone();
     let work_route = warp::path("work")
-        .map(|| {
-            warp::reply::json(&serde_json::json!({
-                "work": "local_slot_data",
-                "poh_hash": "local_poh_hash",
-                "target": "0000ffff"  // Easy local target for alpha
-            }))
+        .and(warp::get())
+        .map(move || match work_open.open_work() {
+            Some(work) => ApiResponse::success(None, Some(work.slot))
+                .with_data(serde_json::json!({
+                    "slot": work.slot,
+                    "leader": work.leader.to_string(),
+                    "poh_hash": hex::encode(work.poh_hash),
+                    "parent_hash": hex::encode(&work.parent_hash),
+                    "merkle_root": hex::encode(work.merkle_root()),
+                    "transactions": work.transactions.len(),
+                    "target": hex::encode(&work.target),
+                    "header": hex::encode(work.header()),
+                    "nonce_encoding": "u64 big-endian, appended to header",
+                    "scrypt": { "log_n": 10, "r": 1, "p": 1, "salt": "", "output_len": 32 },
+                }))
+                .into_reply(),
+            None => ApiResponse::failure(ErrorCode::NotFound, "No open work: this node is not leading a slot right now").into_reply(),
         });
 
-// Local Alpha: Add /submit_block for miner
-let submit_block_route = warp::path("submit_block")
-    .and(warp::post())
-    .and(warp::body::json())
-    .map(move |body: serde_json::Value| {
-        info!("Local Alpha: Block submitted: {:?}", body);
-        warp::reply::with_status("Block Accepted (Local Alpha)", warp::http::StatusCode::OK)
-    });
+    let ledger_submit_block = ledger.clone();
+    let submit_block_route = warp::path("submit_block")
+        .and(warp::post())
+        .and(warp::body::json())
+        .map(move |body: SubmitBlockRequest| match work_server.submit(body.slot, body.nonce, &ledger_submit_block) {
+            Ok(block) => {
+                info!("Local Alpha: Miner solved slot {} with nonce {}", block.slot, block.nonce);
+                ApiResponse::success(None, Some(block.slot))
+                    .with_data(serde_json::json!({
+                        "hash": hex::encode(&block.hash),
+                        "transactions": block.transactions.len(),
+                    }))
+                    .into_reply()
+            }
+            Err(e) => {
+                info!("Local Alpha: Miner submission for slot {} rejected: {}", body.slot, e);
+                ApiResponse::from_consensus_error(&e).into_reply()
+            }
+        });
 
     let airdrop = warp::path!("airdrop" / String / u64)
         .map(move |address: String, amount: u64| {
             match ledger_airdrop.lock() {
                 Ok(mut ledger) => match ledger.airdrop(&address, amount) {
-                    Ok(()) => warp::reply::json(&format!("Local Alpha: Airdrop successful - {} XRS to {}", amount / 1_000_000_000, address)),
-                    Err(e) => warp::reply::json(&format!("Local Alpha: Airdrop failed: {}", e)),
+                    Ok(()) => {
+                        let slot = ledger.get_last_block().map(|b| b.slot).unwrap_or(0u64);
+                        ApiResponse::success(None, Some(slot)).into_reply()
+                    }
+                    Err(e) => ApiResponse::from_ledger_error(&e).into_reply(),
                 },
-                Err(e) => warp::reply::json(&format!("Local Alpha: Airdrop failed: Mutex poisoned - {}", e)),
+                Err(e) => ApiResponse::failure(ErrorCode::Internal, format!("Mutex poisoned - {}", e)).into_reply(),
             }
         });
 
     let submit_transaction = warp::path!("submit_transaction")
+        .and(warp::post())
         .and(warp::body::json())
         .map(move |req: SubmitTransactionRequest| {
             let tx_bytes = match base64::decode(&req.tx) {
                 Ok(bytes) => bytes,
-                Err(e) => return warp::reply::json(&format!("Local Alpha: Invalid transaction: {}", e)),
+                Err(e) => return ApiResponse::failure(ErrorCode::InvalidEncoding, format!("Invalid base64: {}", e)).into_reply(),
             };
             let tx: Transaction = match bincode::deserialize(&tx_bytes) {
                 Ok(tx) => tx,
-                Err(e) => return warp::reply::json(&format!("Local Alpha: Invalid transaction: {}", e)),
+                Err(e) => return ApiResponse::failure(ErrorCode::InvalidEncoding, format!("Invalid transaction: {}", e)).into_reply(),
+            };
+            let signature = match tx.signatures.first() {
+                Some(sig) => sig.to_string(),
+                None => return ApiResponse::failure(ErrorCode::InvalidSignature, "Transaction has no signatures").into_reply(),
+            };
+            // Validate against current state, then queue for the next block's leader
+            let slot = match ledger_submit.lock() {
+                Ok(ledger) => match ledger.check_transaction(&tx) {
+                    Ok(_) => ledger.get_last_block().map(|b| b.slot).unwrap_or(0u64),
+                    Err(e) => return ApiResponse::from_ledger_error(&e).with_signature(signature).into_reply(),
+                },
+                Err(e) => return ApiResponse::failure(ErrorCode::Internal, format!("Mutex poisoned - {}", e)).into_reply(),
             };
             match network_submit.lock() {
-                Ok(mut network) => match ledger_submit.lock() {
-                    Ok(mut ledger) => {
-                        let slot = ledger.get_last_block().map(|b| b.slot).unwrap_or(0u64);
-                        match ledger.add_transaction(tx.clone(), slot) {
-                            Ok(()) => {
-                                network.broadcast_transaction(&tx);
-                                warp::reply::json(&"Local Alpha: Transaction submitted successfully")
-                            }
-                            Err(e) => warp::reply::json(&format!("Local Alpha: Transaction failed: {}", e)),
-                        }
+                Ok(mut network) => match network.broadcast_transaction(&tx) {
+                    Ok(()) => ApiResponse::success(Some(signature), Some(slot)).into_reply(),
+                    Err(e) => ApiResponse::failure(ErrorCode::from(&e), e.to_string())
+                        .with_signature(signature)
+                        .into_reply(),
+                },
+                Err(e) => ApiResponse::failure(ErrorCode::Internal, format!("Mutex poisoned - {}", e)).into_reply(),
+            }
+        });
+
+    let latest_blockhash = warp::path!("latest_blockhash")
+        .and(warp::get())
+        .map(move || match ledger_blockhash.lock() {
+            Ok(ledger) => {
+                let slot = ledger.get_last_block().map(|b| b.slot).unwrap_or(0u64);
+                ApiResponse::success(None, Some(slot))
+                    .with_data(serde_json::json!({
+                        "blockhash": ledger.latest_blockhash().to_string(),
+                        "max_age_blocks": MAX_RECENT_BLOCKHASHES,
+                    }))
+                    .into_reply()
+            }
+            Err(e) => ApiResponse::failure(ErrorCode::Internal, format!("Mutex poisoned - {}", e)).into_reply(),
+        });
+
+    let account_info = warp::path!("account" / String)
+        .and(warp::get())
+        .map(move |address: String| {
+            let pubkey = match Pubkey::try_from(address.as_str()) {
+                Ok(pubkey) => pubkey,
+                Err(_) => return ApiResponse::failure(ErrorCode::InvalidPubkey, format!("Invalid pubkey: {}", address)).into_reply(),
+            };
+            match ledger_account.lock() {
+                Ok(ledger) => {
+                    let slot = ledger.get_last_block().map(|b| b.slot).unwrap_or(0u64);
+                    match ledger.get_account(&pubkey) {
+                        Some(account) => ApiResponse::success(None, Some(slot))
+                            .with_data(serde_json::json!({
+                                "pubkey": address,
+                                "lamports": account.lamports,
+                                "owner": account.owner.to_string(),
+                                "data": base64::encode(&account.data),
+                                "executable": account.executable,
+                                "space": account.data.len(),
+                                "rent_exempt_minimum": accounts::minimum_balance(account.data.len()),
+                            }))
+                            .into_reply(),
+                        None => ApiResponse::failure(ErrorCode::NotFound, format!("Account not found: {}", address)).into_reply(),
                     }
-                    Err(e) => warp::reply::json(&format!("Local Alpha: Transaction failed: Mutex poisoned - {}", e)),
+                }
+                Err(e) => ApiResponse::failure(ErrorCode::Internal, format!("Mutex poisoned - {}", e)).into_reply(),
+            }
+        });
+
+    let account_proof = warp::path!("account_proof" / String / u64)
+        .and(warp::get())
+        .map(move |address: String, slot: u64| {
+            let pubkey = match Pubkey::try_from(address.as_str()) {
+                Ok(pubkey) => pubkey,
+                Err(_) => return ApiResponse::failure(ErrorCode::InvalidPubkey, format!("Invalid pubkey: {}", address)).into_reply(),
+            };
+            match ledger_proof.lock() {
+                Ok(ledger) => match ledger.get_account_proof(&pubkey, slot) {
+                    Ok(proof) => ApiResponse::success(None, Some(proof.slot))
+                        .with_data(serde_json::to_value(&proof).unwrap_or_default())
+                        .into_reply(),
+                    Err(e) => ApiResponse::from_ledger_error(&e).into_reply(),
+                },
+                Err(e) => ApiResponse::failure(ErrorCode::Internal, format!("Mutex poisoned - {}", e)).into_reply(),
+            }
+        });
+
+    let tx_proof = warp::path!("tx_proof" / String)
+        .and(warp::get())
+        .map(move |signature: String| {
+            let parsed = match signature.parse::<Signature>() {
+                Ok(parsed) => parsed,
+                Err(_) => return ApiResponse::failure(ErrorCode::InvalidSignature, format!("Invalid signature: {}", signature)).into_reply(),
+            };
+            match ledger_tx_proof.lock() {
+                Ok(ledger) => match ledger.get_tx_proof(&parsed) {
+                    Ok(proof) => ApiResponse::success(Some(signature), Some(proof.slot))
+                        .with_data(serde_json::to_value(&proof).unwrap_or_default())
+                        .into_reply(),
+                    Err(e) => ApiResponse::from_ledger_error(&e).with_signature(signature).into_reply(),
                 },
-                Err(e) => warp::reply::json(&format!("Local Alpha: Transaction failed: Mutex poisoned - {}", e)),
+                Err(e) => ApiResponse::failure(ErrorCode::Internal, format!("Mutex poisoned - {}", e)).into_reply(),
+            }
+        });
+
+    let block = warp::path!("block" / u64)
+        .and(warp::get())
+        .map(move |slot: u64| match ledger_block.lock() {
+            Ok(ledger) => match ledger.get_block(slot) {
+                Some(block) => ApiResponse::success(None, Some(slot))
+                    .with_data(serde_json::to_value(&block).unwrap_or_default())
+                    .into_reply(),
+                None => ApiResponse::from_ledger_error(&LedgerError::SlotNotFound(slot)).into_reply(),
+            },
+            Err(e) => ApiResponse::failure(ErrorCode::Internal, format!("Mutex poisoned - {}", e)).into_reply(),
+        });
+
+    let stake_info = warp::path!("stake" / String)
+        .and(warp::get())
+        .map(move |address: String| {
+            let owner = match Pubkey::try_from(address.as_str()) {
+                Ok(pubkey) => pubkey,
+                Err(_) => return ApiResponse::failure(ErrorCode::InvalidPubkey, format!("Invalid pubkey: {}", address)).into_reply(),
+            };
+            match ledger_stake.lock() {
+                Ok(ledger) => {
+                    let slot = ledger.get_last_block().map(|b| b.slot).unwrap_or(0u64);
+                    match ledger.get_stake_account(&owner) {
+                        Some(stake) => ApiResponse::success(None, Some(slot))
+                            .with_data(serde_json::json!({
+                                "owner": address,
+                                "stake_account": staking::stake_address(&owner).to_string(),
+                                "validator": stake.validator.to_string(),
+                                "amount": stake.amount,
+                                "activating": stake.activating,
+                                "deactivating": stake.deactivating,
+                                "unbonding": stake.unbonding,
+                                "unlock_epoch": stake.unlock_epoch,
+                                "withdrawable": stake.withdrawable(staking::epoch(slot + 1)),
+                                "slashable": stake.slashable(),
+                                "epoch": staking::epoch(slot),
+                                "last_updated": stake.last_updated,
+                                "validator_reputation": ledger.get_validator_account(&stake.validator).map(|v| v.reputation),
+                                "validator_stake": ledger.get_stakes().get(&stake.validator).copied().unwrap_or(0),
+                            }))
+                            .into_reply(),
+                        None => ApiResponse::failure(ErrorCode::NotFound, format!("No stake account for {}", address)).into_reply(),
+                    }
+                }
+                Err(e) => ApiResponse::failure(ErrorCode::Internal, format!("Mutex poisoned - {}", e)).into_reply(),
+            }
+        });
+
+    let validator_info = warp::path!("validator" / String)
+        .and(warp::get())
+        .map(move |address: String| {
+            let identity = match Pubkey::try_from(address.as_str()) {
+                Ok(pubkey) => pubkey,
+                Err(_) => return ApiResponse::failure(ErrorCode::InvalidPubkey, format!("Invalid pubkey: {}", address)).into_reply(),
+            };
+            match ledger_validator.lock() {
+                Ok(ledger) => {
+                    let slot = ledger.get_last_block().map(|b| b.slot).unwrap_or(0u64);
+                    let total_stake = ledger.get_stakes().get(&identity).copied().unwrap_or(0);
+                    let effective_stake = ledger.get_effective_stakes().get(&identity).copied().unwrap_or(0);
+                    let validator = ledger.get_validator_account(&identity);
+                    if validator.is_none() && total_stake == 0 {
+                        return ApiResponse::failure(ErrorCode::NotFound, format!("No validator account or delegations for {}", address)).into_reply();
+                    }
+                    ApiResponse::success(None, Some(slot))
+                        .with_data(serde_json::json!({
+                            "identity": address,
+                            "validator_account": staking::validator_address(&identity).to_string(),
+                            "commission": validator.as_ref().map(|v| v.commission).unwrap_or(staking::DEFAULT_COMMISSION),
+                            "registration": validator.as_ref().and_then(|v| v.registration.as_ref()).map(|r| serde_json::json!({
+                                "vote_account": r.vote_account.to_string(),
+                                "network_address": r.network_address,
+                            })),
+                            "active": ledger.get_active_validators().contains(&identity),
+                            "pending_rewards": validator.as_ref().map(|v| v.pending_rewards).unwrap_or(0),
+                            "epoch_credits": validator.as_ref().map(|v| v.epoch_credits).unwrap_or(0),
+                            "reputation": validator.as_ref().map(|v| v.reputation).unwrap_or(staking::MAX_REPUTATION),
+                            "missed_slots": validator.as_ref().map(|v| v.missed_slots).unwrap_or(0),
+                            "produced_slots": validator.as_ref().map(|v| v.produced_slots).unwrap_or(0),
+                            "leader_weight": validator.as_ref().map(|v| v.weighted_stake(effective_stake)).unwrap_or(0),
+                            "offenses": validator.as_ref().map(|v| v.offenses).unwrap_or(0),
+                            "slashed": validator.as_ref().map(|v| v.slashed).unwrap_or(0),
+                            "pending_slash": validator.as_ref().is_some_and(|v| v.pending_slash),
+                            "jailed_until": ledger.jailed_until(&identity, slot),
+                            "total_stake": total_stake,
+                            "effective_stake": effective_stake,
+                            "delegators": ledger.get_delegator_count(&identity),
+                            "epoch": staking::epoch(slot),
+                        }))
+                        .into_reply()
+                }
+                Err(e) => ApiResponse::failure(ErrorCode::Internal, format!("Mutex poisoned - {}", e)).into_reply(),
+            }
+        });
+
+    // Per-epoch reward summaries, newest first
+    let rewards_route = warp::path!("rewards")
+        .and(warp::get())
+        .map(move || match ledger_rewards.lock() {
+            Ok(ledger) => {
+                let slot = ledger.get_last_block().map(|b| b.slot).unwrap_or(0u64);
+                let epochs: Vec<serde_json::Value> = ledger
+                    .get_rewards_history()
+                    .iter()
+                    .rev()
+                    .map(|rewards| {
+                        serde_json::json!({
+                            "epoch": rewards.epoch,
+                            "slot": rewards.slot,
+                            "inflation_bps": rewards.inflation_bps,
+                            "inflation": rewards.inflation,
+                            "total_credits": rewards.total_credits,
+                            "distributed": rewards.payouts.iter().map(|payout| payout.lamports).sum::<u64>(),
+                            "payouts": rewards.payouts.len(),
+                        })
+                    })
+                    .collect();
+                ApiResponse::success(None, Some(slot)).with_data(serde_json::json!(epochs)).into_reply()
+            }
+            Err(e) => ApiResponse::failure(ErrorCode::Internal, format!("Mutex poisoned - {}", e)).into_reply(),
+        });
+
+    // Rewards paid to an owner's stake account, or as commission to a validator identity
+    let account_rewards_route = warp::path!("rewards" / String)
+        .and(warp::get())
+        .map(move |address: String| {
+            let owner = match Pubkey::try_from(address.as_str()) {
+                Ok(pubkey) => pubkey,
+                Err(_) => return ApiResponse::failure(ErrorCode::InvalidPubkey, format!("Invalid pubkey: {}", address)).into_reply(),
+            };
+            let stake_account = staking::stake_address(&owner);
+            match ledger_account_rewards.lock() {
+                Ok(ledger) => {
+                    let slot = ledger.get_last_block().map(|b| b.slot).unwrap_or(0u64);
+                    let rewards: Vec<serde_json::Value> = ledger
+                        .get_rewards_history()
+                        .iter()
+                        .rev()
+                        .flat_map(|rewards| {
+                            rewards
+                                .payouts
+                                .iter()
+                                .filter(|payout| payout.pubkey == owner || payout.pubkey == stake_account)
+                                .map(move |payout| {
+                                    serde_json::json!({
+                                        "epoch": rewards.epoch,
+                                        "slot": rewards.slot,
+                                        "pubkey": payout.pubkey.to_string(),
+                                        "kind": payout.kind,
+                                        "lamports": payout.lamports,
+                                    })
+                                })
+                        })
+                        .collect();
+                    ApiResponse::success(None, Some(slot)).with_data(serde_json::json!(rewards)).into_reply()
+                }
+                Err(e) => ApiResponse::failure(ErrorCode::Internal, format!("Mutex poisoned - {}", e)).into_reply(),
             }
         });
 
-    let routes = airdrop.or(submit_transaction).or(work_route).or(submit_block_route);
+    // Newest snapshot file as-is (magic + sha256 + gzip), for new nodes to bootstrap from
+    let snapshot_route = warp::path!("snapshot")
+        .and(warp::get())
+        .map(move || {
+            let dir = match ledger_snapshot.lock() {
+                Ok(ledger) => ledger.snapshot_dir(),
+                Err(e) => return ApiResponse::failure(ErrorCode::Internal, format!("Mutex poisoned - {}", e)).into_reply().into_response(),
+            };
+            match snapshot::newest_bytes(&dir) {
+                Some((slot, bytes)) => warp::http::Response::builder()
+                    .header("content-type", "application/octet-stream")
+                    .header("x-snapshot-slot", slot.to_string())
+                    .body(bytes.into())
+                    .unwrap_or_default(),
+                None => ApiResponse::failure(ErrorCode::NotFound, "No snapshot available yet").into_reply().into_response(),
+            }
+        });
+
+    let fee_for_message = warp::path!("fee_for_message")
+        .and(warp::post())
+        .and(warp::body::json())
+        .map(|req: FeeForMessageRequest| {
+            let message_bytes = match base64::decode(&req.message) {
+                Ok(bytes) => bytes,
+                Err(e) => return ApiResponse::failure(ErrorCode::InvalidEncoding, format!("Invalid base64: {}", e)).into_reply(),
+            };
+            let message: Message = match bincode::deserialize(&message_bytes) {
+                Ok(message) => message,
+                Err(e) => return ApiResponse::failure(ErrorCode::InvalidEncoding, format!("Invalid message: {}", e)).into_reply(),
+            };
+            let fee = fees::calculate_fee(&message);
+            ApiResponse::success(None, None)
+                .with_data(serde_json::json!({
+                    "fee": fee.total(),
+                    "base_fee": fee.base_fee,
+                    "priority_fee": fee.priority_fee,
+                    "compute_unit_limit": fee.compute_unit_limit,
+                    "compute_unit_price": fee.compute_unit_price,
+                    "burned": fee.burned(),
+                    "leader_share": fee.leader_share(),
+                }))
+                .into_reply()
+        });
+
+    let mempool = warp::path!("mempool")
+        .and(warp::get())
+        .map(move || match tx_pool_stats.lock() {
+            Ok(pool) => {
+                let stats = serde_json::to_value(pool.stats()).unwrap_or_default();
+                ApiResponse::success(None, None).with_data(stats).into_reply()
+            }
+            Err(e) => ApiResponse::failure(ErrorCode::Internal, format!("Mutex poisoned - {}", e)).into_reply(),
+        });
+
+    let openapi = warp::path!("openapi.json")
+        .and(warp::get())
+        .map(|| warp::reply::json(&api::openapi_spec()));
+
+    let routes = airdrop
+        .or(submit_transaction)
+        .or(work_route)
+        .or(submit_block_route)
+        .or(latest_blockhash)
+        .or(account_info)
+        .or(account_proof)
+        .or(tx_proof)
+        .or(block)
+        .or(stake_info)
+        .or(validator_info)
+        .or(rewards_route)
+        .or(account_rewards_route)
+        .or(snapshot_route)
+        .or(fee_for_message)
+        .or(mempool)
+        .or(openapi)
+        .recover(api::handle_rejection);
 
     info!("Local Alpha: P2P network started on port 4000 (127.0.0.1 only - no TLS, Patent Pending)");
-    info!("Local Alpha: HTTP endpoints started on http://127.0.0.1:4001 (airdrop, submit_transaction, work, submit_block)");
+    info!("Local Alpha: HTTP endpoints started on http://127.0.0.1:4001 (airdrop, submit_transaction, latest_blockhash, account, account_proof, tx_proof, block, stake, validator, rewards, snapshot, fee_for_message, mempool, work, submit_block, openapi.json)");
 
     tokio::select! {
         _ = async {
@@ -261,18 +639,7 @@ let submit_block_route = warp::path("submit_block")
                     if let Ok(msg) = bincode::deserialize::<NetworkMessage>(&buf[0..n]) {
                         match msg {
                             NetworkMessage::AuthRequest(signature, node_id) => {
-                                let is_authenticated = {
-                                    let network_guard = network.lock().unwrap();
-                                    let validators = network_guard.validators.lock().unwrap();
-                                    let pubkey = validators.iter().find(|&&p| p.to_string() == node_id).copied();
-                                    drop(validators);
-                                    if let Some(pubkey) = pubkey {
-                                        let mut network_guard = network.lock().unwrap();
-                                        network_guard.authenticate_node(&node_id, &signature, &pubkey)
-                                    } else {
-                                        false
-                                    }
-                                };
+                                let is_authenticated = network.lock().unwrap().authenticate_node(&node_id, &signature);
                                 if is_authenticated {
                                     info!("Local Alpha: Authenticated node: {}", node_id);
                                     if let Err(e) = stream.write_all(b"XRS Auth Ack").await {
@@ -282,9 +649,10 @@ let submit_block_route = warp::path("submit_block")
                             }
                             NetworkMessage::Transaction(tx) => {
                                 if tx.verify().is_ok() {
-                                    info!("Local Alpha: Valid tx from {}: {:?}", ip, tx.signatures[0]);
-                                    network.lock().unwrap().broadcast_transaction(&tx);
-                                    if let Err(e) = stream.write_all(b"XRS Tx Ack").await {
+                                    info!("Local Alpha: Valid tx from {}: {:?}", ip, tx.signatures.first());
+                                    let queued = network.lock().unwrap().broadcast_transaction(&tx).is_ok();
+                                    let ack: &[u8] = if queued { b"XRS Tx Ack" } else { b"XRS Tx Rejected" };
+                                    if let Err(e) = stream.write_all(ack).await {
                                         error!("Write failed to {}: {}", ip, e);
                                     }
                                 }
@@ -310,4 +678,34 @@ let submit_block_route = warp::path("submit_block")
             debug!("Local Alpha: Warp server running on port 4001");
         }
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_sdk::signature::{Keypair, Signer};
+    use crate::ledger::test_support;
+
+    #[test]
+    fn only_active_validators_authenticate() {
+        let (member, outsider) = (Keypair::new(), Keypair::new());
+        let mut ledger = test_support::funded_ledger("auth", [member.pubkey()], staking::MIN_STAKE + 10 * 1_000_000_000);
+        let register = [
+            staking::stake(&member.pubkey(), staking::MIN_STAKE),
+            staking::register_validator(&member.pubkey(), &member.pubkey(), "127.0.0.1:4000", staking::DEFAULT_COMMISSION),
+        ];
+        let tx = Transaction::new_signed_with_payer(&register, Some(&member.pubkey()), &[&member], ledger.latest_blockhash());
+        ledger.add_transaction(tx, 1).unwrap();
+        ledger.activate_bootstrap_stake(&member.pubkey()).unwrap();
+        let mut network = Network::new(Arc::new(Mutex::new(Mempool::new())), Arc::new(Mutex::new(ledger)));
+        let auth = |keypair: &Keypair| (keypair.pubkey().to_string(), keypair.sign_message(keypair.pubkey().to_string().as_bytes()));
+
+        let (node_id, signature) = auth(&outsider);
+        assert!(!network.authenticate_node(&node_id, &signature));
+        let (node_id, _) = auth(&member);
+        assert!(!network.authenticate_node(&node_id, &auth(&outsider).1));
+        let (node_id, signature) = auth(&member);
+        assert!(network.authenticate_node(&node_id, &signature));
+        assert!(network.authenticated_nodes.contains_key(&node_id));
+    }
+}
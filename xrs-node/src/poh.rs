@@ -1,5 +1,6 @@
 use sha2::{Digest, Sha256};
 use chrono::Utc;
+use crate::error::ConsensusError;
 
 pub struct PoHRecorder {
     current_hash: [u8; 32],
@@ -14,7 +15,7 @@ impl PoHRecorder {
         }
     }
 
-    pub fn start(&mut self) -> Result<(), Box<dyn std::error::Error>> {
+    pub fn start(&mut self) -> Result<(), ConsensusError> {
         self.tick();
         Ok(())
     }
@@ -22,12 +23,17 @@ impl PoHRecorder {
     pub fn tick(&mut self) {
         let time = Utc::now().timestamp_millis() as u128;
         let mut hasher = Sha256::new();
-        hasher.update(&self.current_hash);
+        hasher.update(self.current_hash);
         hasher.update(time.to_be_bytes());
         self.current_hash = hasher.finalize().into();
         self.slot += 1;
     }
 
+    /// Resumes slot numbering after `slot`, e.g. the last block restored at boot.
+    pub fn set_slot(&mut self, slot: u64) {
+        self.slot = slot;
+    }
+
     pub fn current_slot(&self) -> u64 {
         self.slot
     }
@@ -1,49 +1,167 @@
-use scrypt::{scrypt, Params};
-   use rand::Rng;
+use std::sync::Mutex;
    use std::vec::Vec;
    use crate::ledger::{Block, Ledger};
+   use crate::error::{ConsensusError, LedgerError};
+   use crate::merkle;
+   use crate::miner::{self, MinerPool};
    use log::info;
-   use solana_sdk::{pubkey::Pubkey, signature::Signer};
+   use solana_sdk::{hash::Hash, pubkey::Pubkey, signature::{Keypair, Signature}, transaction::Transaction};
 
-   pub fn propose_block(
-       slot: u64,
-       keypair: &solana_sdk::signature::Keypair,
-       ledger: &std::sync::Arc<std::sync::Mutex<Ledger>>,
-       poh_hash: [u8; 32],
-   ) -> Result<Block, Box<dyn std::error::Error>> {
+   /// A leader slot's block template. A nonce solves it when scrypt over the header followed by
+   /// the nonce as a big-endian u64 falls below `target`.
+   #[derive(Clone, Debug)]
+   pub struct Work {
+       pub slot: u64,
+       pub leader: Pubkey,
+       pub poh_hash: [u8; 32],
+       pub parent_hash: Vec<u8>,
+       pub target: Vec<u8>,
+       /// Transactions the block will carry.
+       pub transactions: Vec<Transaction>,
+   }
+
+   impl Work {
+       /// Slot and leader, then the PoH hash, parent block hash and Merkle root of the candidate
+       /// transactions, so a solution only fits this slot, this fork and this transaction set.
+       /// Transactions that fail execution are dropped from the block, so the root covers the
+       /// candidates as mined rather than the block's `tx_root`.
+       pub fn header(&self) -> Vec<u8> {
+           let mut header = format!("{:?}{}", self.slot, self.leader).into_bytes();
+           header.extend_from_slice(&self.poh_hash);
+           header.extend_from_slice(&self.parent_hash);
+           header.extend_from_slice(self.merkle_root().as_ref());
+           header
+       }
+
+       pub fn merkle_root(&self) -> Hash {
+           merkle::block_tx_root(&self.transactions)
+       }
+
+       pub fn hash(&self, nonce: u64) -> Vec<u8> {
+           miner::scrypt_hash(&self.header(), nonce)
+       }
+
+       /// The block for a solving nonce; the leader signs it once the ledger has executed it.
+       fn into_block(self, hash: Vec<u8>, nonce: u64) -> Block {
+           Block {
+               slot: self.slot,
+               hash,
+               nonce,
+               leader: self.leader,
+               transactions: self.transactions,
+               tx_root: Hash::default(),
+               state_root: Hash::default(),
+               signature: Signature::default(),
+           }
+       }
+   }
+
+   /// Checks that `leader` may propose at `slot` and returns the slot's work, with no transactions
+   /// yet and the target from `adjust_difficulty`.
+   pub fn prepare_work(slot: u64, leader: &Pubkey, ledger: &Ledger, poh_hash: [u8; 32]) -> Result<Work, ConsensusError> {
        let mut target = vec![0u8; 32];
        target[0] = 0x1f;
-       let mut nonce = rand::thread_rng().gen::<u64>();
-       let slot_data = format!("{:?}{}", slot, keypair.pubkey()).into_bytes();
-
-       let ledger_guard = ledger.lock().unwrap();
-       let last_block = ledger_guard.get_last_block();
+       let last_block = ledger.get_last_block();
        if let Some(last) = last_block {
-           target = adjust_difficulty(last, slot, &ledger_guard);
-       }
-       let proposer_stake = ledger_guard.get_stakes().get(&keypair.pubkey()).unwrap_or(&0);
-       if *proposer_stake < 1_000_000_000_000 {
-           return Err("Insufficient stake to propose block".into());
-       }
-       drop(ledger_guard);
-
-       loop {
-           let mut input = slot_data.clone();
-           input.extend_from_slice(&poh_hash);
-           input.extend_from_slice(&nonce.to_be_bytes());
-           let mut hash = vec![0u8; 32];
-           let params = Params::new(10, 1, 1)?;
-           scrypt(&input, &[], &params, &mut hash)?;
-           if hash < target {
-               info!("Block proposed: slot={}, hash={:x?}, nonce={}", slot, hash, nonce);
-               return Ok(Block {
-                   slot,
-                   hash,
-                   nonce,
-                   transactions: Vec::new(),
-               });
+           target = adjust_difficulty(last, slot, ledger);
+       }
+       if let Some(until_epoch) = ledger.jailed_until(leader, slot) {
+           return Err(ConsensusError::Ledger(LedgerError::ValidatorJailed {
+               validator: leader.to_string(),
+               until_epoch,
+           }));
+       }
+       let proposer_stake = *ledger.get_stakes().get(leader).unwrap_or(&0);
+       if proposer_stake < 1_000_000_000_000 {
+           return Err(ConsensusError::InsufficientStake {
+               validator: leader.to_string(),
+               stake: proposer_stake,
+               required: 1_000_000_000_000,
+           });
+       }
+       Ok(Work {
+           slot,
+           leader: *leader,
+           poh_hash,
+           parent_hash: last_block.map(|last| last.hash.clone()).unwrap_or_default(),
+           target,
+           transactions: Vec::new(),
+       })
+   }
+
+   /// Hands the validator's open work to external miners (`/work`, `/submit_block`) while its own
+   /// miner races them; whoever solves the slot first claims the work and makes the block.
+   pub struct WorkServer {
+       keypair: Keypair,
+       open: Mutex<Option<Work>>,
+   }
+
+   impl WorkServer {
+       pub fn new(keypair: Keypair) -> Self {
+           WorkServer { keypair, open: Mutex::new(None) }
+       }
+
+       pub fn publish(&self, work: Work) {
+           *self.open.lock().unwrap() = Some(work);
+       }
+
+       /// Takes back open work nobody solved.
+       pub fn withdraw(&self) -> Option<Work> {
+           self.open.lock().unwrap().take()
+       }
+
+       pub fn open_work(&self) -> Option<Work> {
+           self.open.lock().unwrap().clone()
+       }
+
+       fn open_slot(&self) -> Option<u64> {
+           self.open.lock().unwrap().as_ref().map(|work| work.slot)
+       }
+
+       /// Takes the open work for `slot` if nobody has claimed it yet.
+       fn claim(&self, slot: u64) -> Result<Work, ConsensusError> {
+           let mut open = self.open.lock().unwrap();
+           match open.take() {
+               Some(work) if work.slot == slot => Ok(work),
+               other => {
+                   let open_slot = other.as_ref().map(|work| work.slot);
+                   *open = other;
+                   Err(ConsensusError::StaleWork { slot, open: open_slot })
+               }
+           }
+       }
+
+       /// Verifies an external miner's `nonce` for `slot` and appends the resulting signed block.
+       /// The work is claimed only once the ledger accepts the block; if it refuses, the work stays
+       /// open and its transactions go back to the validator when the slot ends.
+       pub fn submit(&self, slot: u64, nonce: u64, ledger: &Mutex<Ledger>) -> Result<Block, ConsensusError> {
+           let work = self.open_work().filter(|work| work.slot == slot).ok_or_else(|| ConsensusError::StaleWork { slot, open: self.open_slot() })?;
+           let hash = work.hash(nonce);
+           if hash >= work.target {
+               return Err(ConsensusError::InvalidWork { slot, nonce });
+           }
+           // Holding the open work across the append keeps the built-in miner from claiming it meanwhile
+           let mut open = self.open.lock().unwrap();
+           if open.as_ref().map(|open| open.slot) != Some(slot) {
+               return Err(ConsensusError::StaleWork { slot, open: open.as_ref().map(|open| open.slot) });
+           }
+           info!("Block submitted by miner: slot={}, hash={:x?}, nonce={}", slot, hash, nonce);
+           let block = ledger.lock().unwrap().produce_block(work.into_block(hash, nonce), &self.keypair)?;
+           *open = None;
+           Ok(block)
+       }
+   }
+
+   /// Mines the published `work` on the validator's miner pool until it solves it, or until the
+   /// work is withdrawn or an external miner claims it first.
+   pub fn propose_block(work: &Work, work_server: &WorkServer, pool: &MinerPool) -> Result<Block, ConsensusError> {
+       let solved = pool.solve(&work.header(), &work.target, || work_server.open_slot() != Some(work.slot));
+       match solved {
+           Some((nonce, hash)) => {
+               info!("Block proposed: slot={}, hash={:x?}, nonce={}", work.slot, hash, nonce);
+               Ok(work_server.claim(work.slot)?.into_block(hash, nonce))
            }
-           nonce += 1;
+           None => Err(ConsensusError::StaleWork { slot: work.slot, open: work_server.open_slot() }),
        }
    }
 
@@ -62,22 +180,18 @@ use scrypt::{scrypt, Params};
            target[0] = target[0].saturating_add(1);
            info!("Difficulty adjusted harder: target[0]={}", target[0]);
        }
-       if target[0] < 0x1a {
-           target[0] = 0x1a;
-       }
-       if target[0] > 0x1f {
-           target[0] = 0x1f;
-       }
+       target[0] = target[0].clamp(0x1a, 0x1f);
        target
    }
 
+   #[allow(dead_code)]
    pub fn vote(
        block: &Block,
        validators: &[Pubkey],
        ledger: &std::sync::Arc<std::sync::Mutex<Ledger>>,
-   ) -> Result<(), Box<dyn std::error::Error>> {
+   ) -> Result<(), ConsensusError> {
        let ledger_guard = ledger.lock().unwrap();
-       let stakes = ledger_guard.get_stakes();
+       let stakes = ledger_guard.get_effective_stakes();
        let total_stake: u64 = stakes.values().sum();
        let mut votes: u64 = 0;
        for validator in validators {
@@ -94,6 +208,78 @@ use scrypt::{scrypt, Params};
            );
            Ok(())
        } else {
-           Err("Insufficient stake votes".into())
+           Err(ConsensusError::InsufficientVotes { votes, required: total_stake * 2 / 3 })
+       }
+   }
+
+   #[cfg(test)]
+   mod tests {
+       use super::*;
+       use crate::ledger::test_support;
+       use solana_sdk::signature::Signer;
+
+       #[test]
+       fn header_commits_to_parent_and_transactions() {
+           let tx = test_support::transfer(&Keypair::new(), &Pubkey::new_unique(), 1, Hash::default());
+           let work = Work {
+               slot: 7,
+               leader: Pubkey::new_unique(),
+               poh_hash: [1; 32],
+               parent_hash: vec![2; 32],
+               target: vec![0xff; 32],
+               transactions: Vec::new(),
+           };
+           let other_parent = Work { parent_hash: vec![3; 32], ..work.clone() };
+           let with_tx = Work { transactions: vec![tx], ..work.clone() };
+           assert_ne!(work.header(), other_parent.header());
+           assert_ne!(work.header(), with_tx.header());
+           assert!(with_tx.header().ends_with(with_tx.merkle_root().as_ref()));
        }
-   }
\ No newline at end of file
+
+       #[test]
+       fn refused_submission_leaves_work_open() {
+           let ledger = Mutex::new(test_support::ledger("submit"));
+           let work_server = WorkServer::new(Keypair::new());
+           // Led by someone else, so the ledger refuses to sign it
+           let work = Work {
+               slot: 1,
+               leader: Pubkey::new_unique(),
+               poh_hash: [0; 32],
+               parent_hash: Vec::new(),
+               target: vec![0xff; 32],
+               transactions: Vec::new(),
+           };
+           work_server.publish(work);
+           assert!(matches!(
+               work_server.submit(1, 0, &ledger),
+               Err(ConsensusError::Ledger(LedgerError::MaliciousBlock { slot: 1 }))
+           ));
+           assert_eq!(work_server.open_slot(), Some(1));
+       }
+
+       #[test]
+       fn first_valid_submission_claims_the_slot() {
+           let ledger = Mutex::new(test_support::ledger("claim"));
+           let leader = Keypair::new();
+           let work = Work {
+               slot: 1,
+               leader: leader.pubkey(),
+               poh_hash: [0; 32],
+               parent_hash: Vec::new(),
+               target: vec![0xff; 32],
+               transactions: Vec::new(),
+           };
+           let work_server = WorkServer::new(leader);
+           // Nothing hashes below an all-zero target
+           work_server.publish(Work { target: vec![0; 32], ..work.clone() });
+           assert!(matches!(work_server.submit(1, 7, &ledger), Err(ConsensusError::InvalidWork { slot: 1, nonce: 7 })));
+           work_server.publish(work);
+           assert!(matches!(work_server.submit(2, 7, &ledger), Err(ConsensusError::StaleWork { slot: 2, open: Some(1) })));
+
+           let block = work_server.submit(1, 7, &ledger).unwrap();
+           assert_eq!((block.slot, block.nonce), (1, 7));
+           assert!(block.signed_proposal().verify());
+           assert_eq!(ledger.lock().unwrap().get_last_block().map(|last| last.slot), Some(1));
+           assert!(matches!(work_server.submit(1, 8, &ledger), Err(ConsensusError::StaleWork { slot: 1, open: None })));
+       }
+   }
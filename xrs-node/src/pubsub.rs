@@ -0,0 +1,263 @@
+// Patent Pending Copyright © 2025 Xeris Web Co. All rights reserved.
+// XerisCoin PubSub - WebSocket Subscriptions on Explorer (127.0.0.1:8081/ws)
+// Pushes slot, block, account & signature updates (Triple Consensus Feed)
+
+use std::collections::HashMap;
+use futures_util::{SinkExt, StreamExt};
+use tokio::sync::broadcast;
+use warp::ws::{Message, WebSocket, Ws};
+use warp::Filter;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use log::{info, debug, error};
+use crate::ledger::Block;
+
+const CHANNEL_CAPACITY: usize = 1024;
+
+#[derive(Clone, Debug)]
+pub enum Notification {
+    Slot { slot: u64, parent: u64 },
+    Block(Box<Block>),
+    Account { pubkey: String, lamports: u64, slot: u64 },
+    Signature { signature: String, slot: u64, err: Option<String> },
+}
+
+#[derive(Clone)]
+pub struct PubSub {
+    sender: broadcast::Sender<Notification>,
+}
+
+impl PubSub {
+    pub fn new() -> Self {
+        let (sender, _) = broadcast::channel(CHANNEL_CAPACITY);
+        PubSub { sender }
+    }
+
+    pub fn notify(&self, notification: Notification) {
+        // No subscribers connected is not an error for the producer
+        let _ = self.sender.send(notification);
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<Notification> {
+        self.sender.subscribe()
+    }
+}
+
+#[derive(Deserialize)]
+struct RpcRequest {
+    id: Value,
+    method: String,
+    #[serde(default)]
+    params: Vec<Value>,
+}
+
+enum Subscription {
+    Slot,
+    Block,
+    Account(String),
+    Signature(String),
+}
+
+impl Subscription {
+    fn notification_method(&self) -> &'static str {
+        match self {
+            Subscription::Slot => "slotNotification",
+            Subscription::Block => "blockNotification",
+            Subscription::Account(_) => "accountNotification",
+            Subscription::Signature(_) => "signatureNotification",
+        }
+    }
+
+    fn matches(&self, notification: &Notification) -> Option<Value> {
+        match (self, notification) {
+            (Subscription::Slot, Notification::Slot { slot, parent }) => {
+                Some(json!({ "slot": slot, "parent": parent }))
+            }
+            (Subscription::Block, Notification::Block(block)) => {
+                Some(json!({ "slot": block.slot, "block": block }))
+            }
+            (Subscription::Account(wanted), Notification::Account { pubkey, lamports, slot }) if wanted == pubkey => {
+                Some(json!({ "context": { "slot": slot }, "value": { "lamports": lamports } }))
+            }
+            (Subscription::Signature(wanted), Notification::Signature { signature, slot, err }) if wanted == signature => {
+                Some(json!({ "context": { "slot": slot }, "value": { "err": err } }))
+            }
+            _ => None,
+        }
+    }
+}
+
+pub fn ws_route(pubsub: PubSub) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+    warp::path("ws").and(warp::ws()).map(move |ws: Ws| {
+        let pubsub = pubsub.clone();
+        ws.on_upgrade(move |socket| handle_socket(socket, pubsub))
+    })
+}
+
+async fn handle_socket(socket: WebSocket, pubsub: PubSub) {
+    let (mut ws_tx, mut ws_rx) = socket.split();
+    let mut events = pubsub.subscribe();
+    let mut subscriptions: HashMap<u64, Subscription> = HashMap::new();
+    let mut next_id = 0u64;
+    info!("Local Alpha: WebSocket client connected");
+
+    loop {
+        tokio::select! {
+            msg = ws_rx.next() => {
+                let msg = match msg {
+                    Some(Ok(msg)) => msg,
+                    Some(Err(e)) => {
+                        error!("Local Alpha: WebSocket receive failed: {}", e);
+                        break;
+                    }
+                    None => break,
+                };
+                if msg.is_close() {
+                    break;
+                }
+                let Ok(text) = msg.to_str() else { continue };
+                let reply = handle_request(text, &mut subscriptions, &mut next_id);
+                if ws_tx.send(Message::text(reply.to_string())).await.is_err() {
+                    break;
+                }
+            }
+            event = events.recv() => {
+                let notification = match event {
+                    Ok(notification) => notification,
+                    Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                        debug!("Local Alpha: WebSocket client lagged, skipped {} notifications", skipped);
+                        continue;
+                    }
+                    Err(broadcast::error::RecvError::Closed) => break,
+                };
+                let mut fired = Vec::new();
+                for (id, sub) in subscriptions.iter() {
+                    if let Some(result) = sub.matches(&notification) {
+                        let payload = json!({
+                            "jsonrpc": "2.0",
+                            "method": sub.notification_method(),
+                            "params": { "result": result, "subscription": id },
+                        });
+                        fired.push((*id, payload));
+                    }
+                }
+                for (id, payload) in fired {
+                    if ws_tx.send(Message::text(payload.to_string())).await.is_err() {
+                        info!("Local Alpha: WebSocket client disconnected");
+                        return;
+                    }
+                    // Signature subscriptions are one-shot, like Solana's
+                    if matches!(subscriptions.get(&id), Some(Subscription::Signature(_))) {
+                        subscriptions.remove(&id);
+                    }
+                }
+            }
+        }
+    }
+    info!("Local Alpha: WebSocket client disconnected");
+}
+
+fn handle_request(text: &str, subscriptions: &mut HashMap<u64, Subscription>, next_id: &mut u64) -> Value {
+    let req: RpcRequest = match serde_json::from_str(text) {
+        Ok(req) => req,
+        Err(e) => return rpc_error(Value::Null, -32700, &format!("Parse error: {}", e)),
+    };
+    let first_param = req.params.first().and_then(|p| p.as_str()).map(str::to_string);
+    let sub = match req.method.as_str() {
+        "slotSubscribe" => Subscription::Slot,
+        "blockSubscribe" => Subscription::Block,
+        "accountSubscribe" => match first_param {
+            Some(pubkey) => Subscription::Account(pubkey),
+            None => return rpc_error(req.id, -32602, "Invalid params: expected account pubkey"),
+        },
+        "signatureSubscribe" => match first_param {
+            Some(signature) => Subscription::Signature(signature),
+            None => return rpc_error(req.id, -32602, "Invalid params: expected transaction signature"),
+        },
+        "slotUnsubscribe" | "blockUnsubscribe" | "accountUnsubscribe" | "signatureUnsubscribe" => {
+            let removed = req.params.first()
+                .and_then(|p| p.as_u64())
+                .map(|id| subscriptions.remove(&id).is_some())
+                .unwrap_or(false);
+            return json!({ "jsonrpc": "2.0", "result": removed, "id": req.id });
+        }
+        _ => return rpc_error(req.id, -32601, "Method not found"),
+    };
+    let id = *next_id;
+    *next_id += 1;
+    debug!("Local Alpha: {} registered as subscription {}", req.method, id);
+    subscriptions.insert(id, sub);
+    json!({ "jsonrpc": "2.0", "result": id, "id": req.id })
+}
+
+fn rpc_error(id: Value, code: i64, message: &str) -> Value {
+    json!({ "jsonrpc": "2.0", "error": { "code": code, "message": message }, "id": id })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_sdk::pubkey::Pubkey;
+    use warp::test::WsClient;
+    use crate::ledger::test_support;
+
+    async fn request(client: &mut WsClient, method: &str, params: Value) -> Value {
+        let request = json!({ "jsonrpc": "2.0", "id": 1, "method": method, "params": params });
+        client.send_text(request.to_string()).await;
+        next(client).await
+    }
+
+    async fn next(client: &mut WsClient) -> Value {
+        let msg = client.recv().await.expect("socket open");
+        serde_json::from_str(msg.to_str().unwrap()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn subscribers_only_hear_what_they_asked_for() {
+        let pubsub = PubSub::new();
+        let mut client = warp::test::ws().path("/ws").handshake(ws_route(pubsub.clone())).await.unwrap();
+        let slots = request(&mut client, "slotSubscribe", json!([])).await["result"].clone();
+        let blocks = request(&mut client, "blockSubscribe", json!([])).await["result"].clone();
+        let account = request(&mut client, "accountSubscribe", json!(["wanted"])).await["result"].clone();
+        let signature = request(&mut client, "signatureSubscribe", json!(["sig"])).await["result"].clone();
+
+        // Other accounts are filtered out, so the first message is for the wanted one
+        pubsub.notify(Notification::Account { pubkey: "other".to_string(), lamports: 1, slot: 2 });
+        pubsub.notify(Notification::Account { pubkey: "wanted".to_string(), lamports: 5, slot: 2 });
+        let msg = next(&mut client).await;
+        assert_eq!(msg["method"], "accountNotification");
+        assert_eq!(msg["params"]["subscription"], account);
+        assert_eq!(msg["params"]["result"]["value"]["lamports"], 5);
+
+        // Signature subscriptions fire once, then the slot feed is all that is left
+        for _ in 0..2 {
+            pubsub.notify(Notification::Signature { signature: "sig".to_string(), slot: 2, err: None });
+        }
+        pubsub.notify(Notification::Slot { slot: 3, parent: 2 });
+        let msg = next(&mut client).await;
+        assert_eq!(msg["method"], "signatureNotification");
+        assert_eq!(msg["params"]["subscription"], signature);
+        let msg = next(&mut client).await;
+        assert_eq!(msg["method"], "slotNotification");
+        assert_eq!(msg["params"]["subscription"], slots);
+        assert_eq!(msg["params"]["result"], json!({ "slot": 3, "parent": 2 }));
+
+        pubsub.notify(Notification::Block(Box::new(test_support::unsigned_block(3, &Pubkey::new_unique(), Vec::new()))));
+        let msg = next(&mut client).await;
+        assert_eq!(msg["method"], "blockNotification");
+        assert_eq!(msg["params"]["subscription"], blocks);
+        assert_eq!(msg["params"]["result"]["slot"], 3);
+
+        assert_eq!(request(&mut client, "slotUnsubscribe", json!([slots])).await["result"], true);
+        assert_eq!(request(&mut client, "slotUnsubscribe", json!([slots])).await["result"], false);
+    }
+
+    #[tokio::test]
+    async fn bad_requests_get_json_rpc_errors() {
+        let mut client = warp::test::ws().path("/ws").handshake(ws_route(PubSub::new())).await.unwrap();
+        client.send_text("not json").await;
+        assert_eq!(next(&mut client).await["error"]["code"], -32700);
+        assert_eq!(request(&mut client, "accountSubscribe", json!([])).await["error"]["code"], -32602);
+        assert_eq!(request(&mut client, "voteSubscribe", json!([])).await["error"]["code"], -32601);
+    }
+}
@@ -0,0 +1,168 @@
+// Patent Pending Copyright © 2025 Xeris Web Co. All rights reserved.
+// XerisCoin Snapshots - Compressed, Hashed Ledger State for Fast Boot (Local Alpha)
+// Written every snapshot interval; served to peers at /snapshot for bootstrap
+
+use std::fs;
+use std::io::{self, Read, Write};
+use std::net::TcpStream;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use flate2::{read::GzDecoder, write::GzEncoder, Compression};
+use sha2::{Digest, Sha256};
+use serde::{Serialize, Deserialize};
+use solana_sdk::{hash::Hash, pubkey::Pubkey, signature::Signature};
+use log::{info, error};
+use crate::accounts::Account;
+use crate::ledger::Block;
+use crate::merkle::MerkleAccumulator;
+use crate::staking::EpochRewards;
+
+const SNAPSHOT_MAGIC: &[u8; 8] = b"XRSSNAP1";
+pub const SNAPSHOT_VERSION: u32 = 4;
+const SNAPSHOT_EXTENSION: &str = "xsnap";
+/// Older snapshots beyond this many are deleted after a new one is written.
+pub const MAX_SNAPSHOTS: usize = 3;
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Snapshot {
+    pub version: u32,
+    pub slot: u64,
+    pub treasury: Pubkey,
+    pub accounts: Vec<(Pubkey, Account)>,
+    pub stakes: Vec<(Pubkey, u64)>,
+    pub recent_blockhashes: Vec<Hash>,
+    pub signatures: Vec<(Hash, Vec<Signature>)>,
+    pub head: Block,
+    pub tx_accumulator: MerkleAccumulator,
+    pub fees_burned: u64,
+    pub fees_collected: u64,
+    pub rewards_history: Vec<EpochRewards>,
+    /// Sequence number of the first entry logged after `head`; boot replays from here.
+    pub entry_seq: u64,
+}
+
+pub fn snapshot_dir(ledger_path: &str) -> PathBuf {
+    PathBuf::from(format!("{}.snapshots", ledger_path))
+}
+
+/// Serializes and gzips a snapshot, prefixed by magic and the sha256 of the compressed body.
+pub fn encode(snapshot: &Snapshot) -> io::Result<Vec<u8>> {
+    let body = bincode::serialize(snapshot).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(&body)?;
+    let compressed = encoder.finish()?;
+    let mut bytes = Vec::with_capacity(SNAPSHOT_MAGIC.len() + 32 + compressed.len());
+    bytes.extend_from_slice(SNAPSHOT_MAGIC);
+    bytes.extend_from_slice(&Sha256::digest(&compressed));
+    bytes.extend_from_slice(&compressed);
+    Ok(bytes)
+}
+
+/// Checks magic, hash and version before trusting any snapshot bytes.
+pub fn decode(bytes: &[u8]) -> io::Result<Snapshot> {
+    let invalid = |msg: &str| io::Error::new(io::ErrorKind::InvalidData, msg.to_string());
+    if bytes.len() < SNAPSHOT_MAGIC.len() + 32 || &bytes[..SNAPSHOT_MAGIC.len()] != SNAPSHOT_MAGIC {
+        return Err(invalid("not an XRS snapshot"));
+    }
+    let (hash, compressed) = bytes[SNAPSHOT_MAGIC.len()..].split_at(32);
+    if Sha256::digest(compressed).as_slice() != hash {
+        return Err(invalid("snapshot hash mismatch"));
+    }
+    let mut body = Vec::new();
+    GzDecoder::new(compressed).read_to_end(&mut body)?;
+    let snapshot: Snapshot = bincode::deserialize(&body).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+    if snapshot.version != SNAPSHOT_VERSION {
+        return Err(invalid("unsupported snapshot version"));
+    }
+    Ok(snapshot)
+}
+
+fn snapshot_path(dir: &Path, slot: u64) -> PathBuf {
+    dir.join(format!("snapshot-{:012}.{}", slot, SNAPSHOT_EXTENSION))
+}
+
+/// Snapshot files in `dir`, newest slot first.
+fn list(dir: &Path) -> Vec<(u64, PathBuf)> {
+    let mut snapshots: Vec<(u64, PathBuf)> = match fs::read_dir(dir) {
+        Ok(entries) => entries
+            .filter_map(Result::ok)
+            .map(|entry| entry.path())
+            .filter_map(|path| {
+                let name = path.file_name()?.to_str()?;
+                let slot = name.strip_prefix("snapshot-")?.strip_suffix(&format!(".{}", SNAPSHOT_EXTENSION))?;
+                Some((slot.parse().ok()?, path))
+            })
+            .collect(),
+        Err(_) => Vec::new(),
+    };
+    snapshots.sort_by_key(|(slot, _)| std::cmp::Reverse(*slot));
+    snapshots
+}
+
+/// Writes raw snapshot bytes atomically (temp file + rename) and prunes old snapshots.
+pub fn write_bytes(dir: &Path, slot: u64, bytes: &[u8]) -> io::Result<PathBuf> {
+    fs::create_dir_all(dir)?;
+    let path = snapshot_path(dir, slot);
+    let tmp = path.with_extension("tmp");
+    {
+        let mut file = fs::File::create(&tmp)?;
+        file.write_all(bytes)?;
+        file.sync_all()?;
+    }
+    fs::rename(&tmp, &path)?;
+    for (_, old) in list(dir).into_iter().skip(MAX_SNAPSHOTS) {
+        if let Err(e) = fs::remove_file(&old) {
+            error!("Local Alpha: Failed to prune snapshot {}: {}", old.display(), e);
+        }
+    }
+    Ok(path)
+}
+
+pub fn write(dir: &Path, snapshot: &Snapshot) -> io::Result<PathBuf> {
+    write_bytes(dir, snapshot.slot, &encode(snapshot)?)
+}
+
+/// Newest snapshot in `dir` that decodes and passes `is_valid`; corrupt ones are skipped.
+pub fn load_newest<F>(dir: &Path, is_valid: F) -> Option<Snapshot> where F: Fn(&Snapshot) -> bool {
+    for (slot, path) in list(dir) {
+        match fs::read(&path).and_then(|bytes| decode(&bytes)) {
+            Ok(snapshot) if is_valid(&snapshot) => {
+                info!("Local Alpha: Loaded snapshot for slot {} from {}", slot, path.display());
+                return Some(snapshot);
+            }
+            Ok(_) => error!("Local Alpha: Snapshot {} failed state root check, trying older", path.display()),
+            Err(e) => error!("Local Alpha: Snapshot {} unreadable ({}), trying older", path.display(), e),
+        }
+    }
+    None
+}
+
+/// Raw bytes of the newest snapshot in `dir`, for serving to peers.
+pub fn newest_bytes(dir: &Path) -> Option<(u64, Vec<u8>)> {
+    list(dir).into_iter().find_map(|(slot, path)| fs::read(path).ok().map(|bytes| (slot, bytes)))
+}
+
+/// Fetches a peer's newest snapshot from `http://<peer>/snapshot` and stores it in `dir`
+/// once it decodes, so the next `Ledger::new` boots from it. The peer's entry sequence means
+/// nothing locally, so the stored copy replays the local entry log from the start.
+pub fn fetch_from_peer(peer: &str, dir: &Path) -> io::Result<u64> {
+    let mut stream = TcpStream::connect(peer)?;
+    stream.set_read_timeout(Some(Duration::from_secs(30)))?;
+    write!(stream, "GET /snapshot HTTP/1.0\r\nHost: {}\r\n\r\n", peer)?;
+    let mut response = Vec::new();
+    stream.read_to_end(&mut response)?;
+    let header_end = response
+        .windows(4)
+        .position(|w| w == b"\r\n\r\n")
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "malformed HTTP response"))?;
+    let status_line = String::from_utf8_lossy(&response[..header_end]).lines().next().unwrap_or_default().to_string();
+    if !status_line.contains(" 200 ") {
+        return Err(io::Error::other(format!("peer returned {}", status_line)));
+    }
+    let bytes = &response[header_end + 4..];
+    let mut snapshot = decode(bytes)?;
+    snapshot.entry_seq = 0;
+    write(dir, &snapshot)?;
+    info!("Local Alpha: Fetched snapshot for slot {} from {} ({} bytes)", snapshot.slot, peer, bytes.len());
+    Ok(snapshot.slot)
+}
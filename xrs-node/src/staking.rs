@@ -1,84 +1,1021 @@
-use solana_sdk::{pubkey::Pubkey, clock::Clock, program_error::ProgramError, sysvar::Sysvar};
-use log::info;
+// Patent Pending Copyright © 2025 Xeris Web Co. All rights reserved.
+// XerisCoin Stake Program - Stake, Unstake, Delegate & Commission over On-Chain Stake Accounts (Local Alpha)
+// Stake changes, inflation and reward splits take effect at epoch boundaries
 
-#[allow(dead_code)]
+use solana_sdk::{
+    hash::{hashv, Hash},
+    instruction::{AccountMeta, CompiledInstruction, Instruction},
+    message::Message,
+    pubkey::Pubkey,
+    signature::{Keypair, Signature, Signer},
+};
+#[allow(deprecated)]
+use solana_sdk::system_program;
+use serde::{Serialize, Deserialize};
+use std::collections::BTreeSet;
+use std::net::SocketAddr;
+use log::debug;
+use crate::accounts::{self, Account};
+use crate::error::LedgerError;
+use crate::system_processor::instruction_account;
+
+/// Program that owns every stake account.
+pub const ID: Pubkey = Pubkey::new_from_array(*b"XerisStakeProgram111111111111111");
+/// Account holding the chain's [`StakeConfig`], written once at genesis.
+pub const CONFIG_ID: Pubkey = Pubkey::new_from_array(*b"XerisStakeConfig1111111111111111");
+/// Account holding the current epoch's [`LeaderSchedule`], rewritten at every epoch boundary.
+pub const SCHEDULE_ID: Pubkey = Pubkey::new_from_array(*b"XerisLeaderSchedule1111111111111");
+/// Seed deriving an owner's stake account from its pubkey.
+pub const STAKE_SEED: &str = "stake";
+/// Seed deriving a validator's account from its identity pubkey.
+pub const VALIDATOR_SEED: &str = "validator";
+/// Commission a validator keeps until it sets its own, in percent.
+pub const DEFAULT_COMMISSION: u8 = 10;
+/// Rewards are distributed to delegators when the first block of a new epoch lands.
+pub const SLOTS_PER_EPOCH: u64 = 100;
+/// Smallest stake a new stake account may start with, and the least active stake that puts a
+/// registered validator in the active set.
+pub const MIN_STAKE: u64 = 1_000_000_000_000;
+/// Reputation a new validator starts with, and the most it can hold.
+pub const MAX_REPUTATION: u64 = 100;
+/// Reputation a validator loses for each scheduled slot it leaves without a block.
+pub const MISSED_SLOT_PENALTY: u64 = 2;
+/// Reputation a validator regains for each scheduled slot it fills.
+pub const PRODUCED_SLOT_CREDIT: u64 = 1;
+/// Unbonding period used when the genesis config does not set one.
+pub const DEFAULT_UNBONDING_EPOCHS: u64 = 2;
+/// Epochs of reward history kept by the ledger.
+pub const MAX_REWARD_HISTORY: usize = 64;
+/// Reputation a slashed validator loses.
+pub const SLASH_REPUTATION_PENALTY: u64 = 25;
+
+/// Staking inflation schedule, in basis points of the total supply per year.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(default)]
+pub struct Inflation {
+    pub initial_bps: u64,
+    /// Floor the rate tapers down to.
+    pub terminal_bps: u64,
+    /// Fraction of the rate dropped each year, in basis points.
+    pub taper_bps: u64,
+    pub epochs_per_year: u64,
+}
+
+impl Default for Inflation {
+    fn default() -> Self {
+        Inflation {
+            initial_bps: 800,
+            terminal_bps: 150,
+            taper_bps: 1500,
+            // 400ms slots
+            epochs_per_year: 365 * 24 * 3600 * 1000 / 400 / SLOTS_PER_EPOCH,
+        }
+    }
+}
+
+impl Inflation {
+    /// Yearly rate in effect during `epoch`.
+    pub fn rate_bps(&self, epoch: u64) -> u64 {
+        let years = epoch / self.epochs_per_year.max(1);
+        let mut rate = self.initial_bps;
+        for _ in 0..years {
+            if rate <= self.terminal_bps {
+                break;
+            }
+            rate = rate * 10_000u64.saturating_sub(self.taper_bps) / 10_000;
+        }
+        rate.max(self.terminal_bps)
+    }
+
+    /// Lamports minted for stakers at the end of `epoch`, given the total supply.
+    pub fn epoch_inflation(&self, epoch: u64, supply: u64) -> u64 {
+        (supply as u128 * self.rate_bps(epoch) as u128 / 10_000 / self.epochs_per_year.max(1) as u128) as u64
+    }
+}
+
+/// Chain-wide stake parameters, fixed at genesis.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(default)]
+pub struct StakeConfig {
+    /// Whole epochs deactivated stake stays locked (and slashable) before it can be withdrawn.
+    pub unbonding_epochs: u64,
+    pub inflation: Inflation,
+    /// Share of each delegated stake burned on proven equivocation, in basis points.
+    pub slash_bps: u64,
+    /// Whole epochs a slashed validator is left out of the leader schedule after the current one.
+    pub jail_epochs: u64,
+    /// Largest share of the total active stake one validator counts for in leader and vote
+    /// weight, in basis points; stake above it still earns rewards.
+    pub max_stake_bps: u64,
+}
+
+impl Default for StakeConfig {
+    fn default() -> Self {
+        StakeConfig {
+            unbonding_epochs: DEFAULT_UNBONDING_EPOCHS,
+            inflation: Inflation::default(),
+            slash_bps: 500,
+            jail_epochs: 2,
+            max_stake_bps: 1_000,
+        }
+    }
+}
+
+impl StakeConfig {
+    /// Most stake one validator counts for in consensus, out of `total` active stake.
+    pub fn stake_cap(&self, total: u64) -> u64 {
+        (total as u128 * self.max_stake_bps.min(10_000) as u128 / 10_000) as u64
+    }
+}
+
+/// A statement a validator signs. Signing two different ones of the same kind for the same
+/// slot is equivocation.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub enum Attestation {
+    /// Block proposal: the proof-of-work hash and nonce the leader mined for `slot`, and the
+    /// transaction and state roots of the block body.
+    Proposal { slot: u64, hash: Vec<u8>, nonce: u64, tx_root: Hash, state_root: Hash },
+    /// Vote for the block with `hash` at `slot`.
+    Vote { slot: u64, hash: Vec<u8> },
+}
+
+impl Attestation {
+    pub fn slot(&self) -> u64 {
+        match self {
+            Attestation::Proposal { slot, .. } | Attestation::Vote { slot, .. } => *slot,
+        }
+    }
+
+    fn conflicts_with(&self, other: &Attestation) -> bool {
+        let same_kind = matches!(
+            (self, other),
+            (Attestation::Proposal { .. }, Attestation::Proposal { .. }) | (Attestation::Vote { .. }, Attestation::Vote { .. })
+        );
+        same_kind && self.slot() == other.slot() && self != other
+    }
+
+    /// Bytes the validator signs.
+    pub fn message(&self) -> Vec<u8> {
+        bincode::serialize(self).expect("attestation serializes")
+    }
+
+    pub fn sign(self, keypair: &Keypair) -> SignedAttestation {
+        let signature = keypair.sign_message(&self.message());
+        SignedAttestation { validator: keypair.pubkey(), attestation: self, signature }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct SignedAttestation {
+    pub validator: Pubkey,
+    pub attestation: Attestation,
+    pub signature: Signature,
+}
+
+impl SignedAttestation {
+    pub fn verify(&self) -> bool {
+        self.signature.verify(self.validator.as_ref(), &self.attestation.message())
+    }
+}
+
+/// Proof that a validator signed two conflicting attestations for the same slot. Anyone can
+/// check it with nothing but the two signatures.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct SlashingEvidence {
+    pub first: SignedAttestation,
+    pub second: SignedAttestation,
+}
+
+impl SlashingEvidence {
+    /// Returns the offending validator and the slot of the offense.
+    pub fn verify(&self) -> Result<(Pubkey, u64), String> {
+        if self.first.validator != self.second.validator {
+            return Err("attestations are from different validators".to_string());
+        }
+        if !self.first.attestation.conflicts_with(&self.second.attestation) {
+            return Err("attestations do not conflict".to_string());
+        }
+        if !self.first.verify() || !self.second.verify() {
+            return Err(format!("attestation not signed by {}", self.first.validator));
+        }
+        Ok((self.first.validator, self.first.attestation.slot()))
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RewardKind {
+    /// Delegator share, added to a stake account.
+    Staking,
+    /// Commission and rounding dust, paid to a validator identity.
+    Commission,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct RewardPayout {
+    pub pubkey: Pubkey,
+    pub kind: RewardKind,
+    pub lamports: u64,
+}
+
+/// Everything paid out at the end of one epoch.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct EpochRewards {
+    pub epoch: u64,
+    /// Slot of the first block of the next epoch, which paid the rewards.
+    pub slot: u64,
+    pub inflation_bps: u64,
+    /// Lamports minted as inflation; the rest of the payouts are pooled block rewards.
+    pub inflation: u64,
+    pub total_credits: u64,
+    pub payouts: Vec<RewardPayout>,
+}
+
+/// Splits an epoch's inflation between validators by points: active stake times vote credits.
+pub fn inflation_shares(inflation: u64, validators: &[(Pubkey, u64, u64)]) -> Vec<(Pubkey, u64)> {
+    let points = |stake: u64, credits: u64| stake as u128 * credits as u128;
+    let total: u128 = validators.iter().map(|(_, stake, credits)| points(*stake, *credits)).sum();
+    if total == 0 {
+        return Vec::new();
+    }
+    validators
+        .iter()
+        .map(|(pubkey, stake, credits)| (*pubkey, (inflation as u128 * points(*stake, *credits) / total) as u64))
+        .filter(|(_, share)| *share > 0)
+        .collect()
+}
+
+/// State kept in a stake account's data.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
 pub struct StakeAccount {
     pub owner: Pubkey,
+    /// Validator whose leader-schedule and vote weight this stake counts toward.
+    pub validator: Pubkey,
+    /// Active stake: counts toward the validator's weight and earns rewards.
     pub amount: u64,
-    pub last_updated: i64,
-    pub reputation_score: u64,
+    /// Lamports staked this epoch, added to `amount` at the next epoch boundary.
+    pub activating: u64,
+    /// Active lamports leaving `amount` at the next epoch boundary.
+    pub deactivating: u64,
+    /// Deactivated lamports locked until `unlock_epoch`; still slashable meanwhile.
+    pub unbonding: u64,
+    pub unlock_epoch: u64,
+    /// Slot of the last stake change.
+    pub last_updated: u64,
 }
 
-#[allow(dead_code)]
-pub fn initialize_stake(owner: Pubkey, amount: u64) -> Result<StakeAccount, ProgramError> {
-    if amount < 1_000_000_000_000 {
-        return Err(ProgramError::InsufficientFunds);
+impl StakeAccount {
+    /// Lamports that are or were bonded and can still be slashed.
+    pub fn slashable(&self) -> u64 {
+        self.amount + self.unbonding
+    }
+
+    /// Unbonded lamports the owner may withdraw in `epoch`.
+    pub fn withdrawable(&self, epoch: u64) -> u64 {
+        if epoch >= self.unlock_epoch { self.unbonding } else { 0 }
+    }
+
+    /// Applies pending activation and deactivation at the start of `epoch`.
+    pub fn activate(&mut self, epoch: u64, config: &StakeConfig) {
+        self.amount += self.activating;
+        self.activating = 0;
+        if self.deactivating > 0 {
+            // Further deactivations join the bucket and restart its lock
+            self.amount -= self.deactivating;
+            self.unbonding += self.deactivating;
+            self.unlock_epoch = epoch + config.unbonding_epochs;
+            self.deactivating = 0;
+        }
+    }
+}
+
+/// State kept in a validator account's data.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct ValidatorAccount {
+    pub identity: Pubkey,
+    /// Percent of each epoch's rewards the validator keeps before delegators are paid.
+    pub commission: u8,
+    /// Rewards earned this epoch, held in the account's lamports until distribution.
+    pub pending_rewards: u64,
+    /// Vote credits this epoch: one per block the validator led.
+    pub epoch_credits: u64,
+    /// Set by verified equivocation evidence; the ledger slashes and jails at the end of the block.
+    pub pending_slash: bool,
+    /// Slots of every punished offense; evidence for one of them again is refused.
+    pub offense_slots: BTreeSet<u64>,
+    pub offenses: u64,
+    /// First epoch the validator may lead again.
+    pub jailed_until: u64,
+    /// Lamports burned from its delegations over all offenses.
+    pub slashed: u64,
+    /// Out of [`MAX_REPUTATION`]; scales the validator's leader weight and inflation share.
+    pub reputation: u64,
+    /// Scheduled slots the validator left without a block, over its lifetime.
+    pub missed_slots: u64,
+    /// Scheduled slots the validator filled, over its lifetime.
+    pub produced_slots: u64,
+    /// Set by RegisterValidator; only registered validators join the active set.
+    pub registration: Option<ValidatorRegistration>,
+}
+
+/// What a validator declares when it registers.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct ValidatorRegistration {
+    pub vote_account: Pubkey,
+    /// Address peers reach the validator at, as host:port.
+    pub network_address: String,
+}
+
+impl ValidatorAccount {
+    pub fn is_jailed(&self, epoch: u64) -> bool {
+        self.pending_slash || epoch < self.jailed_until
+    }
+
+    /// `stake` scaled by the validator's reputation.
+    pub fn weighted_stake(&self, stake: u64) -> u64 {
+        (stake as u128 * self.reputation.min(MAX_REPUTATION) as u128 / MAX_REPUTATION as u128) as u64
+    }
+
+    pub fn missed_slot(&mut self) {
+        self.missed_slots += 1;
+        self.reputation = self.reputation.saturating_sub(MISSED_SLOT_PENALTY);
+    }
+
+    pub fn produced_slot(&mut self) {
+        self.produced_slots += 1;
+        self.reputation = (self.reputation + PRODUCED_SLOT_CREDIT).min(MAX_REPUTATION);
     }
-    let stake_account = StakeAccount {
-        owner,
+}
+
+/// The active set of one epoch: registered validators with at least [`MIN_STAKE`] active, weighted by
+/// reputation-scaled stake. Each slot's leader is drawn from a hash of the slot, so every node derives
+/// the same schedule from the same accounts.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct LeaderSchedule {
+    pub epoch: u64,
+    /// Validator identities and weights, sorted by identity.
+    pub leaders: Vec<(Pubkey, u64)>,
+}
+
+impl LeaderSchedule {
+    pub fn leader(&self, slot: u64) -> Option<Pubkey> {
+        let total: u128 = self.leaders.iter().map(|(_, weight)| *weight as u128).sum();
+        if total == 0 || epoch(slot) != self.epoch {
+            return None;
+        }
+        let seed = hashv(&[b"leader", &slot.to_le_bytes()]).to_bytes();
+        let mut point = u128::from_le_bytes(seed[..16].try_into().expect("16 bytes")) % total;
+        for (identity, weight) in &self.leaders {
+            if point < *weight as u128 {
+                return Some(*identity);
+            }
+            point -= *weight as u128;
+        }
+        None
+    }
+}
+
+/// Data of an account owned by the stake program.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+enum StakeProgramState {
+    Stake(StakeAccount),
+    Validator(ValidatorAccount),
+    Config(StakeConfig),
+    Schedule(LeaderSchedule),
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub enum StakeInstruction {
+    /// Moves `lamports` from the owner's balance into its stake account, creating it on first use.
+    /// The stake activates at the next epoch boundary.
+    /// Accounts: [signer, writable] owner, [writable] stake account
+    Stake { lamports: u64 },
+    /// Deactivates `lamports` of active stake at the next epoch boundary; they then unbond.
+    /// Accounts: [signer, writable] owner, [writable] stake account
+    Unstake { lamports: u64 },
+    /// Points the stake at `validator`.
+    /// Accounts: [signer] owner, [writable] stake account
+    Delegate { validator: Pubkey },
+    /// Sets the validator's commission in percent, creating its validator account on first use.
+    /// Accounts: [signer, writable] identity, [writable] validator account
+    SetCommission { commission: u8 },
+    /// Returns `lamports` of unbonded stake to the owner; withdrawing the last of it closes the stake account.
+    /// Accounts: [signer, writable] owner, [writable] stake account
+    Withdraw { lamports: u64 },
+    /// Reports a validator's equivocation; once verified, its delegations are slashed at the end of the block.
+    /// Accounts: [signer, writable] reporter, [writable] offender's validator account (created by the reporter if missing)
+    ReportEquivocation { evidence: Box<SlashingEvidence> },
+    /// Registers the signer as a validator, or updates its registration. It joins the active set at
+    /// the next epoch boundary once it has the minimum active stake.
+    /// Accounts: [signer, writable] identity, [writable] validator account
+    RegisterValidator { vote_account: Pubkey, network_address: String, commission: u8 },
+}
+
+pub fn epoch(slot: u64) -> u64 {
+    slot / SLOTS_PER_EPOCH
+}
+
+/// Stake account address of `owner`.
+pub fn stake_address(owner: &Pubkey) -> Pubkey {
+    Pubkey::create_with_seed(owner, STAKE_SEED, &ID).expect("stake seed is valid")
+}
+
+/// Validator account address of `identity`.
+pub fn validator_address(identity: &Pubkey) -> Pubkey {
+    Pubkey::create_with_seed(identity, VALIDATOR_SEED, &ID).expect("validator seed is valid")
+}
+
+fn program_state(account: &Account) -> Option<StakeProgramState> {
+    if account.owner != ID {
+        return None;
+    }
+    bincode::deserialize(&account.data).ok()
+}
+
+fn encode_state(state: &StakeProgramState) -> Vec<u8> {
+    bincode::serialize(state).expect("stake program state serializes")
+}
+
+/// Decoded stake state of an account, if it is a stake account.
+pub fn stake_state(account: &Account) -> Option<StakeAccount> {
+    match program_state(account)? {
+        StakeProgramState::Stake(state) => Some(state),
+        _ => None,
+    }
+}
+
+/// Decoded validator state of an account, if it is a validator account.
+pub fn validator_state(account: &Account) -> Option<ValidatorAccount> {
+    match program_state(account)? {
+        StakeProgramState::Validator(state) => Some(state),
+        _ => None,
+    }
+}
+
+/// Decoded stake config, if `account` is the config account.
+pub fn config_state(account: &Account) -> Option<StakeConfig> {
+    match program_state(account)? {
+        StakeProgramState::Config(config) => Some(config),
+        _ => None,
+    }
+}
+
+/// Decoded leader schedule, if `account` is the schedule account.
+pub fn schedule_state(account: &Account) -> Option<LeaderSchedule> {
+    match program_state(account)? {
+        StakeProgramState::Schedule(schedule) => Some(schedule),
+        _ => None,
+    }
+}
+
+/// The schedule account; it holds no lamports, so the schedule never changes the supply.
+pub fn new_schedule_account(schedule: &LeaderSchedule) -> Account {
+    Account {
+        lamports: 0,
+        owner: ID,
+        data: encode_state(&StakeProgramState::Schedule(schedule.clone())),
+        executable: false,
+    }
+}
+
+/// The genesis stake config account.
+pub fn new_config_account(config: &StakeConfig) -> Account {
+    let data = encode_state(&StakeProgramState::Config(config.clone()));
+    Account {
+        lamports: accounts::minimum_balance(data.len()),
+        owner: ID,
+        data,
+        executable: false,
+    }
+}
+
+/// Writes validator state into an existing validator account.
+pub fn set_validator_state(account: &mut Account, state: &ValidatorAccount) {
+    account.data = encode_state(&StakeProgramState::Validator(state.clone()));
+}
+
+/// Writes stake state into an existing stake account.
+pub fn set_stake_state(account: &mut Account, state: &StakeAccount) {
+    account.data = encode_state(&StakeProgramState::Stake(state.clone()));
+}
+
+/// An empty validator account for `identity` holding just its rent reserve.
+pub fn new_validator_account(identity: &Pubkey, commission: u8) -> Account {
+    let data = encode_state(&StakeProgramState::Validator(ValidatorAccount {
+        identity: *identity,
+        commission,
+        pending_rewards: 0,
+        epoch_credits: 0,
+        pending_slash: false,
+        offense_slots: BTreeSet::new(),
+        offenses: 0,
+        jailed_until: 0,
+        slashed: 0,
+        reputation: MAX_REPUTATION,
+        missed_slots: 0,
+        produced_slots: 0,
+        registration: None,
+    }));
+    Account {
+        lamports: accounts::minimum_balance(data.len()),
+        owner: ID,
+        data,
+        executable: false,
+    }
+}
+
+/// Splits an epoch's `rewards` between a validator and its delegations, weighted by stake.
+/// Returns the validator's cut (commission plus rounding dust) and each stake account's share.
+pub fn split_rewards(rewards: u64, commission: u8, delegations: &[(Pubkey, u64)]) -> (u64, Vec<(Pubkey, u64)>) {
+    let total: u128 = delegations.iter().map(|(_, amount)| *amount as u128).sum();
+    if total == 0 {
+        return (rewards, Vec::new());
+    }
+    let to_delegators = rewards - rewards * commission.min(100) as u64 / 100;
+    let shares: Vec<(Pubkey, u64)> = delegations
+        .iter()
+        .map(|(pubkey, amount)| (*pubkey, (to_delegators as u128 * *amount as u128 / total) as u64))
+        .filter(|(_, share)| *share > 0)
+        .collect();
+    let paid: u64 = shares.iter().map(|(_, share)| share).sum();
+    (rewards - paid, shares)
+}
+
+/// A funded stake account for `owner`, delegated to itself: `amount` staked plus the rent reserve.
+pub fn new_stake_account(owner: &Pubkey, amount: u64, slot: u64) -> Account {
+    let state = StakeAccount {
+        owner: *owner,
+        validator: *owner,
         amount,
-        last_updated: Clock::get()?.unix_timestamp,
-        reputation_score: 100,
+        activating: 0,
+        deactivating: 0,
+        unbonding: 0,
+        unlock_epoch: 0,
+        last_updated: slot,
     };
-    info!("Stake initialized: owner={}, amount={} XRS", owner, amount / 1_000_000_000);
-    Ok(stake_account)
+    let data = encode_state(&StakeProgramState::Stake(state));
+    Account {
+        lamports: accounts::minimum_balance(data.len()) + amount,
+        owner: ID,
+        data,
+        executable: false,
+    }
+}
+
+fn instruction(owner: &Pubkey, owner_writable: bool, data: &StakeInstruction) -> Instruction {
+    let owner_meta = if owner_writable { AccountMeta::new(*owner, true) } else { AccountMeta::new_readonly(*owner, true) };
+    let target = match data {
+        StakeInstruction::SetCommission { .. } | StakeInstruction::RegisterValidator { .. } => validator_address(owner),
+        _ => stake_address(owner),
+    };
+    Instruction::new_with_bincode(ID, data, vec![owner_meta, AccountMeta::new(target, false)])
+}
+
+pub fn stake(owner: &Pubkey, lamports: u64) -> Instruction {
+    instruction(owner, true, &StakeInstruction::Stake { lamports })
 }
 
 #[allow(dead_code)]
-pub fn stake(stake_account: &mut StakeAccount, amount: u64, total_stake: u64) -> Result<(), ProgramError> {
-    if stake_account.amount + amount > total_stake / 10 {
-        return Err(ProgramError::InvalidArgument);
-    }
-    stake_account.amount = stake_account.amount.checked_add(amount).ok_or(ProgramError::ArithmeticOverflow)?;
-    stake_account.last_updated = Clock::get()?.unix_timestamp;
-    stake_account.reputation_score += 10;
-    info!(
-        "Staked: owner={}, amount={} XRS, new total={} XRS",
-        stake_account.owner,
-        amount / 1_000_000_000,
-        stake_account.amount / 1_000_000_000
-    );
-    Ok(())
+pub fn withdraw(owner: &Pubkey, lamports: u64) -> Instruction {
+    instruction(owner, true, &StakeInstruction::Withdraw { lamports })
 }
 
 #[allow(dead_code)]
-pub fn unstake(stake_account: &mut StakeAccount, amount: u64) -> Result<(), ProgramError> {
-    stake_account.amount = stake_account.amount.checked_sub(amount).ok_or(ProgramError::InsufficientFunds)?;
-    stake_account.last_updated = Clock::get()?.unix_timestamp;
-    info!(
-        "Unstaked: owner={}, amount={} XRS, new total={} XRS",
-        stake_account.owner,
-        amount / 1_000_000_000,
-        stake_account.amount / 1_000_000_000
-    );
-    Ok(())
+pub fn unstake(owner: &Pubkey, lamports: u64) -> Instruction {
+    instruction(owner, true, &StakeInstruction::Unstake { lamports })
 }
 
 #[allow(dead_code)]
-pub fn claim_rewards(stake_account: &mut StakeAccount) -> Result<(), ProgramError> {
-    let elapsed = Clock::get()?.unix_timestamp - stake_account.last_updated;
-    let apy = 0.07;
-    let reward = (stake_account.amount as f64 * apy * elapsed as f64 / (365 * 24 * 3600) as f64) as u64;
-    stake_account.amount = stake_account.amount.checked_add(reward).ok_or(ProgramError::ArithmeticOverflow)?;
-    stake_account.last_updated = Clock::get()?.unix_timestamp;
-    info!(
-        "Rewards claimed: owner={}, reward={} XRS, new total={} XRS",
-        stake_account.owner,
-        reward / 1_000_000_000,
-        stake_account.amount / 1_000_000_000
-    );
-    Ok(())
+pub fn delegate(owner: &Pubkey, validator: &Pubkey) -> Instruction {
+    instruction(owner, false, &StakeInstruction::Delegate { validator: *validator })
 }
 
 #[allow(dead_code)]
-pub fn slash(stake_account: &mut StakeAccount, amount: u64) -> Result<(), ProgramError> {
-    stake_account.amount = stake_account.amount.checked_sub(amount).ok_or(ProgramError::InsufficientFunds)?;
-    stake_account.reputation_score = stake_account.reputation_score.saturating_sub(25);
-    info!(
-        "Slashed: owner={}, amount={} XRS, new total={} XRS",
-        stake_account.owner,
-        amount / 1_000_000_000,
-        stake_account.amount / 1_000_000_000
-    );
+pub fn report_equivocation(reporter: &Pubkey, evidence: SlashingEvidence) -> Instruction {
+    let validator = validator_address(&evidence.first.validator);
+    Instruction::new_with_bincode(
+        ID,
+        &StakeInstruction::ReportEquivocation { evidence: Box::new(evidence) },
+        vec![AccountMeta::new(*reporter, true), AccountMeta::new(validator, false)],
+    )
+}
+
+pub fn register_validator(identity: &Pubkey, vote_account: &Pubkey, network_address: &str, commission: u8) -> Instruction {
+    instruction(
+        identity,
+        true,
+        &StakeInstruction::RegisterValidator { vote_account: *vote_account, network_address: network_address.to_string(), commission },
+    )
+}
+
+#[allow(dead_code)]
+pub fn set_commission(identity: &Pubkey, commission: u8) -> Instruction {
+    instruction(identity, true, &StakeInstruction::SetCommission { commission })
+}
+
+fn invalid(message: String) -> LedgerError {
+    LedgerError::InvalidInstruction(message)
+}
+
+/// Resolves and checks the signing owner and the program account derived from it by `derive`.
+fn owned_accounts(
+    message: &Message,
+    ix: &CompiledInstruction,
+    owner_writable: bool,
+    derive: fn(&Pubkey) -> Pubkey,
+) -> Result<(usize, usize), LedgerError> {
+    let owner = instruction_account(message, ix, 0)?;
+    let stake = instruction_account(message, ix, 1)?;
+    if !message.is_signer(owner) {
+        return Err(invalid(format!("{} must sign", message.account_keys[owner])));
+    }
+    if owner_writable && !message.is_maybe_writable(owner, None) {
+        return Err(invalid(format!("{} must be writable", message.account_keys[owner])));
+    }
+    if !message.is_maybe_writable(stake, None) {
+        return Err(invalid(format!("{} must be writable", message.account_keys[stake])));
+    }
+    if message.account_keys[stake] != derive(&message.account_keys[owner]) {
+        return Err(invalid(format!(
+            "{} is not the stake program account of {}",
+            message.account_keys[stake], message.account_keys[owner]
+        )));
+    }
+    Ok((owner, stake))
+}
+
+/// Stake state of an existing stake account, checked against its owner.
+fn load_stake(message: &Message, accounts: &[Account], owner: usize, stake: usize) -> Result<StakeAccount, LedgerError> {
+    let state = stake_state(&accounts[stake])
+        .ok_or_else(|| invalid(format!("{} is not a stake account", message.account_keys[stake])))?;
+    if state.owner != message.account_keys[owner] {
+        return Err(invalid(format!("{} does not own {}", message.account_keys[owner], message.account_keys[stake])));
+    }
+    Ok(state)
+}
+
+fn debit(message: &Message, accounts: &mut [Account], index: usize, lamports: u64) -> Result<(), LedgerError> {
+    let balance = accounts[index].lamports;
+    if balance < lamports {
+        return Err(LedgerError::InsufficientFunds {
+            account: message.account_keys[index].to_string(),
+            balance,
+            required: lamports,
+        });
+    }
+    accounts[index].lamports -= lamports;
     Ok(())
-}
\ No newline at end of file
+}
+
+fn credit(accounts: &mut [Account], index: usize, lamports: u64) -> Result<(), LedgerError> {
+    accounts[index].lamports = accounts[index]
+        .lamports
+        .checked_add(lamports)
+        .ok_or_else(|| invalid("lamports overflow".to_string()))?;
+    Ok(())
+}
+
+/// Applies one stake program instruction to `accounts` (aligned with `message.account_keys`)
+/// as part of the block at `slot`.
+pub fn process_instruction(message: &Message, ix: &CompiledInstruction, accounts: &mut [Account], slot: u64) -> Result<(), LedgerError> {
+    let instruction: StakeInstruction = bincode::deserialize(&ix.data)
+        .map_err(|e| invalid(format!("invalid stake instruction: {}", e)))?;
+    match instruction {
+        StakeInstruction::Stake { lamports } => {
+            let (owner, stake) = owned_accounts(message, ix, true, stake_address)?;
+            if accounts[owner].owner != system_program::id() || !accounts[owner].data.is_empty() {
+                return Err(invalid(format!("{} is not a system account", message.account_keys[owner])));
+            }
+            let state = if accounts[stake].is_empty() {
+                if lamports < MIN_STAKE {
+                    return Err(invalid(format!("initial stake {} below minimum {}", lamports, MIN_STAKE)));
+                }
+                // The owner also funds the new account's rent reserve
+                let mut account = new_stake_account(&message.account_keys[owner], lamports, slot);
+                debit(message, accounts, owner, account.lamports)?;
+                let mut state = stake_state(&account).expect("new stake account decodes");
+                state.amount = 0;
+                state.activating = lamports;
+                set_stake_state(&mut account, &state);
+                accounts[stake] = account;
+                state
+            } else {
+                let mut state = load_stake(message, accounts, owner, stake)?;
+                state.activating = state.activating.checked_add(lamports).ok_or_else(|| invalid("stake overflow".to_string()))?;
+                state.last_updated = slot;
+                set_stake_state(&mut accounts[stake], &state);
+                debit(message, accounts, owner, lamports)?;
+                credit(accounts, stake, lamports)?;
+                state
+            };
+            debug!(
+                "Local Alpha: Staked: owner={}, amount={} XRS, activating={} XRS",
+                state.owner,
+                lamports / 1_000_000_000,
+                state.activating / 1_000_000_000
+            );
+            Ok(())
+        }
+        StakeInstruction::Unstake { lamports } => {
+            let (owner, stake) = owned_accounts(message, ix, true, stake_address)?;
+            let mut state = load_stake(message, accounts, owner, stake)?;
+            let active = state.amount - state.deactivating;
+            if lamports > active {
+                return Err(LedgerError::InsufficientFunds {
+                    account: message.account_keys[stake].to_string(),
+                    balance: active,
+                    required: lamports,
+                });
+            }
+            state.deactivating += lamports;
+            state.last_updated = slot;
+            set_stake_state(&mut accounts[stake], &state);
+            debug!(
+                "Local Alpha: Unstaked: owner={}, amount={} XRS, deactivating={} XRS",
+                state.owner,
+                lamports / 1_000_000_000,
+                state.deactivating / 1_000_000_000
+            );
+            Ok(())
+        }
+        StakeInstruction::Withdraw { lamports } => {
+            let (owner, stake) = owned_accounts(message, ix, true, stake_address)?;
+            let mut state = load_stake(message, accounts, owner, stake)?;
+            let withdrawable = state.withdrawable(epoch(slot));
+            if lamports > withdrawable {
+                return Err(LedgerError::InsufficientFunds {
+                    account: message.account_keys[stake].to_string(),
+                    balance: withdrawable,
+                    required: lamports,
+                });
+            }
+            state.unbonding -= lamports;
+            state.last_updated = slot;
+            if state.amount + state.activating + state.unbonding == 0 {
+                // Nothing left staked: close the account and refund its rent reserve too
+                let all = accounts[stake].lamports;
+                accounts[stake] = Account::default();
+                credit(accounts, owner, all)?;
+            } else {
+                set_stake_state(&mut accounts[stake], &state);
+                debit(message, accounts, stake, lamports)?;
+                credit(accounts, owner, lamports)?;
+            }
+            debug!("Local Alpha: Withdrew: owner={}, amount={} XRS", state.owner, lamports / 1_000_000_000);
+            Ok(())
+        }
+        StakeInstruction::Delegate { validator } => {
+            let (owner, stake) = owned_accounts(message, ix, false, stake_address)?;
+            let mut state = load_stake(message, accounts, owner, stake)?;
+            // Bonded and unbonding stake stays answerable for its validator's offenses; only stake
+            // still activating, or a fully withdrawn account, may move
+            if validator != state.validator && state.slashable() > 0 {
+                return Err(invalid(format!(
+                    "{} has {} lamports bonded to {}; unstake and withdraw them before delegating elsewhere",
+                    message.account_keys[stake],
+                    state.slashable(),
+                    state.validator
+                )));
+            }
+            state.validator = validator;
+            state.last_updated = slot;
+            set_stake_state(&mut accounts[stake], &state);
+            debug!("Local Alpha: Delegated: owner={}, {} XRS to {}", state.owner, state.amount / 1_000_000_000, validator);
+            Ok(())
+        }
+        StakeInstruction::SetCommission { commission } => {
+            let (identity, validator) = owned_accounts(message, ix, true, validator_address)?;
+            if commission > 100 {
+                return Err(invalid(format!("commission {}% above 100%", commission)));
+            }
+            if accounts[validator].is_empty() {
+                // The identity funds the new account's rent reserve
+                let account = new_validator_account(&message.account_keys[identity], commission);
+                debit(message, accounts, identity, account.lamports)?;
+                accounts[validator] = account;
+            } else {
+                let mut state = validator_state(&accounts[validator])
+                    .ok_or_else(|| invalid(format!("{} is not a validator account", message.account_keys[validator])))?;
+                state.commission = commission;
+                set_validator_state(&mut accounts[validator], &state);
+            }
+            debug!("Local Alpha: Commission set: validator={}, {}%", message.account_keys[identity], commission);
+            Ok(())
+        }
+        StakeInstruction::RegisterValidator { vote_account, network_address, commission } => {
+            let (identity, validator) = owned_accounts(message, ix, true, validator_address)?;
+            if commission > 100 {
+                return Err(invalid(format!("commission {}% above 100%", commission)));
+            }
+            if network_address.parse::<SocketAddr>().is_err() {
+                return Err(invalid(format!("network address {:?} is not host:port", network_address)));
+            }
+            if accounts[validator].is_empty() {
+                accounts[validator] = Account { lamports: 0, ..new_validator_account(&message.account_keys[identity], commission) };
+            }
+            let mut state = validator_state(&accounts[validator])
+                .ok_or_else(|| invalid(format!("{} is not a validator account", message.account_keys[validator])))?;
+            state.commission = commission;
+            state.registration = Some(ValidatorRegistration { vote_account, network_address: network_address.clone() });
+            set_validator_state(&mut accounts[validator], &state);
+            // The identity tops the rent reserve up to cover the registration
+            let reserve = accounts[validator].lamports.saturating_sub(state.pending_rewards);
+            let top_up = accounts::minimum_balance(accounts[validator].data.len()).saturating_sub(reserve);
+            debit(message, accounts, identity, top_up)?;
+            credit(accounts, validator, top_up)?;
+            debug!(
+                "Local Alpha: Validator registered: identity={}, vote_account={}, address={}, {}%",
+                message.account_keys[identity], vote_account, network_address, commission
+            );
+            Ok(())
+        }
+        StakeInstruction::ReportEquivocation { evidence } => {
+            let reporter = instruction_account(message, ix, 0)?;
+            let validator = instruction_account(message, ix, 1)?;
+            if !message.is_signer(reporter) || !message.is_maybe_writable(reporter, None) {
+                return Err(invalid(format!("{} must sign and be writable", message.account_keys[reporter])));
+            }
+            if !message.is_maybe_writable(validator, None) {
+                return Err(invalid(format!("{} must be writable", message.account_keys[validator])));
+            }
+            let (offender, offense_slot) = evidence.verify().map_err(|e| invalid(format!("invalid slashing evidence: {}", e)))?;
+            if message.account_keys[validator] != validator_address(&offender) {
+                return Err(invalid(format!("{} is not the validator account of {}", message.account_keys[validator], offender)));
+            }
+            if offense_slot > slot {
+                return Err(invalid(format!("offense at slot {} is in the future", offense_slot)));
+            }
+            if accounts[validator].is_empty() {
+                // The reporter funds the account that records the offense
+                let account = new_validator_account(&offender, DEFAULT_COMMISSION);
+                debit(message, accounts, reporter, account.lamports)?;
+                accounts[validator] = account;
+            }
+            let mut state = validator_state(&accounts[validator])
+                .ok_or_else(|| invalid(format!("{} is not a validator account", message.account_keys[validator])))?;
+            if state.offense_slots.contains(&offense_slot) {
+                return Err(invalid(format!("offense of {} at slot {} already punished", offender, offense_slot)));
+            }
+            if state.pending_slash {
+                return Err(invalid(format!("{} already has a slash pending in this block", offender)));
+            }
+            state.pending_slash = true;
+            state.offense_slots.insert(offense_slot);
+            state.offenses += 1;
+            set_validator_state(&mut accounts[validator], &state);
+            // The reporter also funds the rent on the slot it adds to the record
+            let reserve = accounts[validator].lamports.saturating_sub(state.pending_rewards);
+            let top_up = accounts::minimum_balance(accounts[validator].data.len()).saturating_sub(reserve);
+            debit(message, accounts, reporter, top_up)?;
+            credit(accounts, validator, top_up)?;
+            debug!(
+                "Local Alpha: Equivocation by {} at slot {} reported by {}",
+                offender, offense_slot, message.account_keys[reporter]
+            );
+            Ok(())
+        }
+    }
+}
+
+pub fn is_stake_program(program_id: &Pubkey) -> bool {
+    *program_id == ID
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const XRS: u64 = 1_000_000_000;
+
+    /// Runs a Delegate to a new validator against a stake account in the given state.
+    fn redelegate(amount: u64, activating: u64, unbonding: u64) -> Result<StakeAccount, LedgerError> {
+        let owner = Pubkey::new_unique();
+        let validator = Pubkey::new_unique();
+        let message = Message::new(&[delegate(&owner, &validator)], Some(&owner));
+        let mut stake_account = new_stake_account(&owner, amount + unbonding, 0);
+        let mut state = stake_state(&stake_account).unwrap();
+        state.activating = activating;
+        state.unbonding = unbonding;
+        set_stake_state(&mut stake_account, &state);
+        let mut accounts: Vec<Account> = message
+            .account_keys
+            .iter()
+            .map(|key| match key {
+                key if *key == owner => Account::new_system(10 * XRS),
+                key if *key == stake_address(&owner) => stake_account.clone(),
+                _ => Account::default(),
+            })
+            .collect();
+        process_instruction(&message, &message.instructions[0], &mut accounts, 1)?;
+        let index = message.account_keys.iter().position(|key| *key == stake_address(&owner)).unwrap();
+        let state = stake_state(&accounts[index]).unwrap();
+        assert_eq!(state.validator, validator);
+        Ok(state)
+    }
+
+    #[test]
+    fn activating_or_withdrawn_stake_may_redelegate() {
+        assert!(redelegate(0, 1_000 * XRS, 0).is_ok());
+        assert!(redelegate(0, 0, 0).is_ok());
+    }
+
+    /// Runs a ReportEquivocation of `offender` voting twice at `offense_slot` against its current
+    /// validator account, returning the account afterwards.
+    fn report(validator_account: &Account, offender: &Keypair, offense_slot: u64) -> Result<Account, LedgerError> {
+        let reporter = Pubkey::new_unique();
+        let vote = |hash: u8| Attestation::Vote { slot: offense_slot, hash: vec![hash] }.sign(offender);
+        let evidence = SlashingEvidence { first: vote(1), second: vote(2) };
+        let message = Message::new(&[report_equivocation(&reporter, evidence)], Some(&reporter));
+        let mut accounts: Vec<Account> = message
+            .account_keys
+            .iter()
+            .map(|key| if *key == reporter { Account::new_system(10 * XRS) } else { validator_account.clone() })
+            .collect();
+        process_instruction(&message, &message.instructions[0], &mut accounts, 100)?;
+        let index = message.account_keys.iter().position(|key| *key == validator_address(&offender.pubkey())).unwrap();
+        Ok(accounts.swap_remove(index))
+    }
+
+    #[test]
+    fn each_offense_slot_is_punished_once_in_any_order() {
+        let offender = Keypair::new();
+        let mut account = report(&Account::default(), &offender, 50).unwrap();
+        // The ledger clears the flag once it has slashed at the end of the block
+        let mut state = validator_state(&account).unwrap();
+        assert!(state.pending_slash);
+        assert!(matches!(report(&account, &offender, 60), Err(LedgerError::InvalidInstruction(_))));
+        state.pending_slash = false;
+        set_validator_state(&mut account, &state);
+
+        // Evidence for an earlier slot that surfaces later still counts
+        let account = report(&account, &offender, 40).unwrap();
+        let state = validator_state(&account).unwrap();
+        assert_eq!(state.offense_slots, BTreeSet::from([40, 50]));
+        assert_eq!(state.offenses, 2);
+        assert!(account.lamports >= accounts::minimum_balance(account.data.len()));
+        assert!(matches!(report(&account, &offender, 50), Err(LedgerError::InvalidInstruction(_))));
+    }
+
+    #[test]
+    fn split_rewards_pays_commission_and_rounding_dust_to_the_validator() {
+        let (small, large) = (Pubkey::new_unique(), Pubkey::new_unique());
+        // 10% of 1001 is 100; the 901 left splits 1:2 into 300 and 600, so 1 lamport is dust
+        let (validator, shares) = split_rewards(1_001, 10, &[(small, XRS), (large, 2 * XRS)]);
+        assert_eq!(shares, vec![(small, 300), (large, 600)]);
+        assert_eq!(validator, 101);
+        assert_eq!(validator + shares.iter().map(|(_, share)| share).sum::<u64>(), 1_001);
+
+        let (validator, shares) = split_rewards(1_001, 0, &[(small, XRS), (large, 2 * XRS)]);
+        assert_eq!(shares, vec![(small, 333), (large, 667)]);
+        assert_eq!(validator, 1);
+    }
+
+    #[test]
+    fn split_rewards_without_delegated_stake_all_go_to_the_validator() {
+        assert_eq!(split_rewards(500, 10, &[]), (500, Vec::new()));
+        assert_eq!(split_rewards(500, 10, &[(Pubkey::new_unique(), 0)]), (500, Vec::new()));
+        // A zero-stake delegation next to a real one earns nothing
+        let (empty, staked) = (Pubkey::new_unique(), Pubkey::new_unique());
+        assert_eq!(split_rewards(500, 10, &[(empty, 0), (staked, XRS)]), (50, vec![(staked, 450)]));
+        // Commission above 100% is capped
+        assert_eq!(split_rewards(500, 150, &[(staked, XRS)]), (500, Vec::new()));
+    }
+
+    #[test]
+    fn inflation_shares_weigh_stake_by_vote_credits() {
+        let (a, b, c, idle, unstaked) = (Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+        let validators = [(a, XRS, 2), (b, 2 * XRS, 1), (c, XRS, 1), (idle, XRS, 0), (unstaked, 0, 5)];
+        assert_eq!(inflation_shares(1_000, &validators), vec![(a, 400), (b, 400), (c, 200)]);
+        // Rounding only ever mints less than the epoch's inflation, never more
+        let shares = inflation_shares(1_001, &validators);
+        assert_eq!(shares.iter().map(|(_, share)| share).sum::<u64>(), 1_000);
+        assert!(inflation_shares(1_000, &[(idle, XRS, 0), (unstaked, 0, 5)]).is_empty());
+    }
+
+    #[test]
+    fn inflation_tapers_yearly_down_to_the_terminal_rate() {
+        let inflation = Inflation { initial_bps: 800, terminal_bps: 150, taper_bps: 1500, epochs_per_year: 10 };
+        assert_eq!(inflation.rate_bps(9), 800);
+        assert_eq!(inflation.rate_bps(10), 680);
+        assert_eq!(inflation.rate_bps(20), 578);
+        assert_eq!(inflation.rate_bps(10_000), 150);
+        // 8% a year of 1000 XRS over 10 epochs
+        assert_eq!(inflation.epoch_inflation(0, 1_000 * XRS), 8 * XRS);
+    }
+
+    #[test]
+    fn stake_cap_is_a_share_of_the_total() {
+        let config = StakeConfig { max_stake_bps: 1_000, ..StakeConfig::default() };
+        assert_eq!(config.stake_cap(1_000 * XRS), 100 * XRS);
+        assert_eq!(config.stake_cap(0), 0);
+        // A share above 100% caps at the total
+        let uncapped = StakeConfig { max_stake_bps: 20_000, ..StakeConfig::default() };
+        assert_eq!(uncapped.stake_cap(u64::MAX), u64::MAX);
+    }
+
+    #[test]
+    fn slashable_stake_cannot_redelegate() {
+        assert!(matches!(redelegate(1_000 * XRS, 0, 0), Err(LedgerError::InvalidInstruction(_))));
+        assert!(matches!(redelegate(0, 0, 500 * XRS), Err(LedgerError::InvalidInstruction(_))));
+        assert!(matches!(redelegate(1_000 * XRS, 200 * XRS, 0), Err(LedgerError::InvalidInstruction(_))));
+    }
+}
@@ -0,0 +1,430 @@
+// Patent Pending Copyright © 2025 Xeris Web Co. All rights reserved.
+// XerisCoin State Tree - Sparse Merkle Tree over All Accounts (Local Alpha)
+// Content-addressed nodes keep recent blocks' state roots provable without replaying the chain
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+use solana_sdk::pubkey::Pubkey;
+use sha2::{Digest, Sha256};
+use serde::{Serialize, Deserialize};
+use crate::accounts::Account;
+
+pub type NodeHash = [u8; 32];
+
+/// Hash of an empty subtree at any depth.
+pub const EMPTY_HASH: NodeHash = [0u8; 32];
+/// Committed roots a tree keeps provable by default, matching the ledger's default in-memory block window.
+pub const DEFAULT_MAX_VERSIONS: usize = 1000;
+
+const LEAF_PREFIX: u8 = 0x00;
+const INTERNAL_PREFIX: u8 = 0x01;
+
+/// Path of an account in the tree: sha256(pubkey), walked from the most significant bit.
+pub fn account_key(pubkey: &Pubkey) -> NodeHash {
+    Sha256::digest(pubkey.as_ref()).into()
+}
+
+pub fn account_hash(account: &Account) -> NodeHash {
+    Sha256::digest(bincode::serialize(account).unwrap_or_default()).into()
+}
+
+pub fn leaf_hash(key: &NodeHash, value_hash: &NodeHash) -> NodeHash {
+    let mut hasher = Sha256::new();
+    hasher.update([LEAF_PREFIX]);
+    hasher.update(key);
+    hasher.update(value_hash);
+    hasher.finalize().into()
+}
+
+pub fn internal_hash(left: &NodeHash, right: &NodeHash) -> NodeHash {
+    let mut hasher = Sha256::new();
+    hasher.update([INTERNAL_PREFIX]);
+    hasher.update(left);
+    hasher.update(right);
+    hasher.finalize().into()
+}
+
+fn bit(key: &NodeHash, depth: usize) -> bool {
+    key[depth / 8] & (0x80 >> (depth % 8)) != 0
+}
+
+#[derive(Clone, Debug)]
+enum Node {
+    Leaf { pubkey: Pubkey, account: Account },
+    Internal { left: NodeHash, right: NodeHash },
+}
+
+/// Leaf found at the end of a non-inclusion path: another account sharing the queried prefix.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct ProofLeaf {
+    pub pubkey: String,
+    pub value_hash: String,
+}
+
+/// Inclusion (or non-inclusion) proof for one account against a block's state root.
+///
+/// A light client verifies it with [`verify_account_proof`]: hash the terminal node
+/// (the account's leaf, the conflicting `leaf`, or the empty hash), then fold `siblings`
+/// from the deepest upward, placing the running hash left or right by the bit of
+/// `sha256(pubkey)` at that depth. The result must equal `state_root`.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct AccountProof {
+    pub slot: u64,
+    pub state_root: String,
+    pub pubkey: String,
+    pub account: Option<Account>,
+    /// Sibling hashes from the root down to the terminal node, hex-encoded.
+    pub siblings: Vec<String>,
+    pub leaf: Option<ProofLeaf>,
+}
+
+/// Persistent sparse Merkle tree. Subtrees holding a single account collapse into that
+/// account's leaf, so updates touch O(log n) nodes and the shape is canonical for a given state.
+/// Only the newest `max_versions` committed roots stay provable; nodes no kept root reaches are
+/// swept once every `max_versions` commits, so memory tracks the live state plus that window.
+pub struct StateTree {
+    nodes: HashMap<NodeHash, Node>,
+    root: NodeHash,
+    roots: BTreeMap<u64, NodeHash>,
+    max_versions: usize,
+    commits_since_sweep: usize,
+}
+
+impl StateTree {
+    pub fn new() -> Self {
+        Self::with_max_versions(DEFAULT_MAX_VERSIONS)
+    }
+
+    /// A tree keeping the newest `max_versions` committed roots (at least two, so the previous
+    /// root is always there to [`StateTree::revert`] to).
+    pub fn with_max_versions(max_versions: usize) -> Self {
+        StateTree {
+            nodes: HashMap::new(),
+            root: EMPTY_HASH,
+            roots: BTreeMap::new(),
+            max_versions: max_versions.max(2),
+            commits_since_sweep: 0,
+        }
+    }
+
+    /// Root committed for `slot`, or for the latest block before it.
+    pub fn root_at(&self, slot: u64) -> Option<(u64, NodeHash)> {
+        self.roots.range(..=slot).next_back().map(|(slot, root)| (*slot, *root))
+    }
+
+    /// Sets (or with `None`, removes) an account in the working tree.
+    pub fn update(&mut self, pubkey: &Pubkey, account: Option<&Account>) {
+        let key = account_key(pubkey);
+        self.root = self.update_node(self.root, 0, &key, pubkey, account);
+    }
+
+    /// Records the working root as the state root of `slot`, dropping the oldest root beyond
+    /// the version window.
+    pub fn commit(&mut self, slot: u64) -> NodeHash {
+        self.roots.insert(slot, self.root);
+        while self.roots.len() > self.max_versions {
+            self.roots.pop_first();
+        }
+        self.commits_since_sweep += 1;
+        if self.commits_since_sweep >= self.max_versions {
+            self.sweep();
+        }
+        self.root
+    }
+
+    /// Drops every node that neither a kept root nor the working root reaches.
+    fn sweep(&mut self) {
+        let mut live = HashSet::with_capacity(self.nodes.len());
+        let mut pending: Vec<NodeHash> = self.roots.values().copied().chain([self.root]).collect();
+        while let Some(hash) = pending.pop() {
+            if !live.insert(hash) {
+                continue;
+            }
+            if let Some(Node::Internal { left, right }) = self.nodes.get(&hash) {
+                pending.extend([*left, *right]);
+            }
+        }
+        self.nodes.retain(|hash, _| live.contains(hash));
+        self.commits_since_sweep = 0;
+    }
+
+    /// Working root, to hand back to [`StateTree::revert`].
+    pub fn working_root(&self) -> NodeHash {
+        self.root
+    }
+
+    /// Undoes updates and the commit for `slot` made since `working_root` returned `root`.
+    /// Nodes are content-addressed, so the old root still resolves to the old tree as long as it
+    /// is a kept root, as the root committed just before `slot` is.
+    pub fn revert(&mut self, slot: u64, root: NodeHash) {
+        if self.roots.get(&slot) == Some(&self.root) {
+            self.roots.remove(&slot);
+        }
+        self.root = root;
+    }
+
+    fn put_leaf(&mut self, pubkey: &Pubkey, account: &Account) -> NodeHash {
+        let hash = leaf_hash(&account_key(pubkey), &account_hash(account));
+        self.nodes.entry(hash).or_insert_with(|| Node::Leaf { pubkey: *pubkey, account: account.clone() });
+        hash
+    }
+
+    fn put_internal(&mut self, left: NodeHash, right: NodeHash) -> NodeHash {
+        // Keep single-leaf subtrees collapsed so the shape stays canonical after deletes
+        if left == EMPTY_HASH && right == EMPTY_HASH {
+            return EMPTY_HASH;
+        }
+        if left == EMPTY_HASH && matches!(self.nodes.get(&right), Some(Node::Leaf { .. })) {
+            return right;
+        }
+        if right == EMPTY_HASH && matches!(self.nodes.get(&left), Some(Node::Leaf { .. })) {
+            return left;
+        }
+        let hash = internal_hash(&left, &right);
+        self.nodes.entry(hash).or_insert(Node::Internal { left, right });
+        hash
+    }
+
+    fn update_node(&mut self, node: NodeHash, depth: usize, key: &NodeHash, pubkey: &Pubkey, account: Option<&Account>) -> NodeHash {
+        match self.nodes.get(&node).cloned() {
+            None => match account {
+                Some(account) => self.put_leaf(pubkey, account),
+                None => EMPTY_HASH,
+            },
+            Some(Node::Leaf { pubkey: existing, .. }) if existing == *pubkey => match account {
+                Some(account) => self.put_leaf(pubkey, account),
+                None => EMPTY_HASH,
+            },
+            Some(Node::Leaf { pubkey: existing, .. }) => match account {
+                Some(account) => {
+                    let leaf = self.put_leaf(pubkey, account);
+                    self.split(node, &account_key(&existing), leaf, key, depth)
+                }
+                None => node,
+            },
+            Some(Node::Internal { left, right }) => {
+                if bit(key, depth) {
+                    let right = self.update_node(right, depth + 1, key, pubkey, account);
+                    self.put_internal(left, right)
+                } else {
+                    let left = self.update_node(left, depth + 1, key, pubkey, account);
+                    self.put_internal(left, right)
+                }
+            }
+        }
+    }
+
+    /// Pushes two leaves down until their keys diverge.
+    fn split(&mut self, existing: NodeHash, existing_key: &NodeHash, leaf: NodeHash, key: &NodeHash, depth: usize) -> NodeHash {
+        match (bit(existing_key, depth), bit(key, depth)) {
+            (false, true) => self.put_internal(existing, leaf),
+            (true, false) => self.put_internal(leaf, existing),
+            (false, false) => {
+                let left = self.split(existing, existing_key, leaf, key, depth + 1);
+                self.put_internal(left, EMPTY_HASH)
+            }
+            (true, true) => {
+                let right = self.split(existing, existing_key, leaf, key, depth + 1);
+                self.put_internal(EMPTY_HASH, right)
+            }
+        }
+    }
+
+    /// Builds a proof for `pubkey` against the state root committed at (or before) `slot`.
+    pub fn prove(&self, pubkey: &Pubkey, slot: u64) -> Option<AccountProof> {
+        let (slot, root) = self.root_at(slot)?;
+        let key = account_key(pubkey);
+        let mut siblings = Vec::new();
+        let mut node = root;
+        let mut depth = 0;
+        let (account, leaf) = loop {
+            match self.nodes.get(&node) {
+                None => break (None, None),
+                Some(Node::Leaf { pubkey: found, account }) if found == pubkey => break (Some(account.clone()), None),
+                Some(Node::Leaf { pubkey: found, account }) => {
+                    break (
+                        None,
+                        Some(ProofLeaf { pubkey: found.to_string(), value_hash: hex::encode(account_hash(account)) }),
+                    )
+                }
+                Some(Node::Internal { left, right }) => {
+                    if bit(&key, depth) {
+                        siblings.push(hex::encode(left));
+                        node = *right;
+                    } else {
+                        siblings.push(hex::encode(right));
+                        node = *left;
+                    }
+                    depth += 1;
+                }
+            }
+        };
+        Some(AccountProof {
+            slot,
+            state_root: hex::encode(root),
+            pubkey: pubkey.to_string(),
+            account,
+            siblings,
+            leaf,
+        })
+    }
+}
+
+fn decode_hash(hex_str: &str) -> Option<NodeHash> {
+    hex::decode(hex_str).ok()?.try_into().ok()
+}
+
+/// Light-client check of an [`AccountProof`]; needs nothing but the proof itself and a trusted state root.
+pub fn verify_account_proof(proof: &AccountProof) -> bool {
+    let (Ok(pubkey), Some(root)) = (Pubkey::try_from(proof.pubkey.as_str()), decode_hash(&proof.state_root)) else {
+        return false;
+    };
+    let key = account_key(&pubkey);
+    let depth = proof.siblings.len();
+    if depth > 256 {
+        return false;
+    }
+    let mut hash = match (&proof.account, &proof.leaf) {
+        (Some(account), None) => leaf_hash(&key, &account_hash(account)),
+        (None, Some(other)) => {
+            let (Ok(other_pubkey), Some(value_hash)) = (Pubkey::try_from(other.pubkey.as_str()), decode_hash(&other.value_hash)) else {
+                return false;
+            };
+            let other_key = account_key(&other_pubkey);
+            // The other leaf must sit on this account's path, otherwise it proves nothing
+            if other_key == key || (0..depth).any(|d| bit(&other_key, d) != bit(&key, d)) {
+                return false;
+            }
+            leaf_hash(&other_key, &value_hash)
+        }
+        (None, None) => EMPTY_HASH,
+        (Some(_), Some(_)) => return false,
+    };
+    for d in (0..depth).rev() {
+        let Some(sibling) = decode_hash(&proof.siblings[d]) else {
+            return false;
+        };
+        hash = if bit(&key, d) { internal_hash(&sibling, &hash) } else { internal_hash(&hash, &sibling) };
+    }
+    hash == root
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tree_with(accounts: &[(Pubkey, u64)], slot: u64) -> StateTree {
+        let mut tree = StateTree::new();
+        for (pubkey, lamports) in accounts {
+            tree.update(pubkey, Some(&Account::new_system(*lamports)));
+        }
+        tree.commit(slot);
+        tree
+    }
+
+    #[test]
+    fn inclusion_proofs_verify() {
+        let accounts: Vec<(Pubkey, u64)> = (1..=16).map(|n| (Pubkey::new_unique(), n * 100)).collect();
+        let tree = tree_with(&accounts, 1);
+        for (pubkey, lamports) in &accounts {
+            let proof = tree.prove(pubkey, 1).unwrap();
+            assert_eq!(proof.account.as_ref().map(|a| a.lamports), Some(*lamports));
+            assert!(verify_account_proof(&proof));
+        }
+    }
+
+    #[test]
+    fn non_inclusion_proofs_verify() {
+        let empty = tree_with(&[], 1);
+        let proof = empty.prove(&Pubkey::new_unique(), 1).unwrap();
+        assert!(proof.account.is_none() && proof.leaf.is_none());
+        assert!(verify_account_proof(&proof));
+
+        // A lone account collapses the tree into its leaf, so any other key ends on it
+        let single = tree_with(&[(Pubkey::new_unique(), 5)], 1);
+        let proof = single.prove(&Pubkey::new_unique(), 1).unwrap();
+        assert!(proof.account.is_none() && proof.leaf.is_some());
+        assert!(verify_account_proof(&proof));
+
+        // Two accounts on the same side of the root leave the other side empty
+        let first = Pubkey::new_unique();
+        let side = bit(&account_key(&first), 0);
+        let keyed = |wanted: bool| std::iter::repeat_with(Pubkey::new_unique).find(|p| bit(&account_key(p), 0) == wanted).unwrap();
+        let tree = tree_with(&[(first, 1), (keyed(side), 2)], 1);
+        let proof = tree.prove(&keyed(!side), 1).unwrap();
+        assert!(proof.account.is_none() && proof.leaf.is_none());
+        assert_eq!(proof.siblings.len(), 1);
+        assert!(verify_account_proof(&proof));
+    }
+
+    #[test]
+    fn tampered_proofs_fail() {
+        let accounts: Vec<(Pubkey, u64)> = (1..=8).map(|n| (Pubkey::new_unique(), n * 10)).collect();
+        let tree = tree_with(&accounts, 1);
+        let proof = tree.prove(&accounts[0].0, 1).unwrap();
+        assert!(verify_account_proof(&proof));
+
+        let mut forged = proof.clone();
+        forged.account.as_mut().unwrap().lamports += 1;
+        assert!(!verify_account_proof(&forged));
+
+        let mut forged = proof.clone();
+        forged.siblings[0] = hex::encode([7u8; 32]);
+        assert!(!verify_account_proof(&forged));
+
+        let mut forged = proof.clone();
+        forged.state_root = hex::encode([7u8; 32]);
+        assert!(!verify_account_proof(&forged));
+
+        let mut forged = proof.clone();
+        forged.pubkey = accounts[1].0.to_string();
+        assert!(!verify_account_proof(&forged));
+
+        // Claiming a present account is absent cannot reproduce the root
+        let mut forged = proof.clone();
+        forged.account = None;
+        assert!(!verify_account_proof(&forged));
+
+        // Nor can presenting some other account's leaf as the conflicting one
+        let other = tree.prove(&accounts[1].0, 1).unwrap();
+        let mut forged = proof;
+        forged.leaf = Some(ProofLeaf {
+            pubkey: other.pubkey,
+            value_hash: hex::encode(account_hash(other.account.as_ref().unwrap())),
+        });
+        forged.account = None;
+        assert!(!verify_account_proof(&forged));
+    }
+
+    #[test]
+    fn old_versions_are_pruned() {
+        let accounts: Vec<Pubkey> = (0..4).map(|_| Pubkey::new_unique()).collect();
+        let mut tree = StateTree::with_max_versions(3);
+        for slot in 1..=100u64 {
+            tree.update(&accounts[slot as usize % accounts.len()], Some(&Account::new_system(slot)));
+            tree.commit(slot);
+        }
+        assert_eq!(tree.roots.len(), 3);
+        assert!(tree.prove(&accounts[0], 97).is_none());
+        for slot in 98..=100 {
+            assert!(verify_account_proof(&tree.prove(&accounts[0], slot).unwrap()));
+        }
+        // Four accounts need at most seven nodes per version; without sweeping this would hold hundreds
+        assert!(tree.nodes.len() <= 3 * 7 + 7 * 3, "{} nodes kept", tree.nodes.len());
+    }
+
+    #[test]
+    fn revert_survives_a_sweep() {
+        let pubkey = Pubkey::new_unique();
+        let mut tree = StateTree::with_max_versions(2);
+        tree.update(&pubkey, Some(&Account::new_system(1)));
+        tree.commit(1);
+        let before = tree.working_root();
+        tree.update(&pubkey, Some(&Account::new_system(2)));
+        tree.commit(2);
+        tree.revert(2, before);
+        let proof = tree.prove(&pubkey, 2).unwrap();
+        assert_eq!(proof.slot, 1);
+        assert_eq!(proof.account.map(|a| a.lamports), Some(1));
+        assert_eq!(tree.working_root(), before);
+    }
+}
@@ -0,0 +1,473 @@
+// Patent Pending Copyright © 2025 Xeris Web Co. All rights reserved.
+// XerisCoin Ledger Storage - Pluggable Backends for Blocks, Accounts & Entry Log (Local Alpha)
+// MemoryStore for benchmarks and tests, RedbStore (embedded on-disk key-value) for nodes
+
+use std::collections::BTreeMap;
+use std::ops::Range;
+use redb::{Database, ReadableTable, ReadableTableMetadata, TableDefinition};
+use solana_sdk::pubkey::Pubkey;
+use crate::accounts::Account;
+use crate::error::LedgerError;
+use crate::ledger::{Block, LedgerEntry};
+
+/// One write in a [`WriteBatch`].
+#[derive(Clone, Debug)]
+pub enum WriteOp {
+    PutBlock(Block),
+    DeleteBlocks(Range<u64>),
+    PutAccount(Pubkey, Account),
+    DeleteAccount(Pubkey),
+    PutEntry(u64, LedgerEntry),
+    DeleteEntries(Range<u64>),
+}
+
+/// Writes that a store applies all together or not at all.
+#[derive(Clone, Debug, Default)]
+pub struct WriteBatch {
+    ops: Vec<WriteOp>,
+}
+
+impl WriteBatch {
+    pub fn new() -> Self {
+        WriteBatch::default()
+    }
+
+    pub fn put_block(&mut self, block: &Block) {
+        self.ops.push(WriteOp::PutBlock(block.clone()));
+    }
+
+    pub fn delete_blocks(&mut self, slots: Range<u64>) {
+        self.ops.push(WriteOp::DeleteBlocks(slots));
+    }
+
+    /// Stores `account`, or deletes the key when the account no longer exists.
+    pub fn put_account(&mut self, pubkey: &Pubkey, account: Option<&Account>) {
+        self.ops.push(match account {
+            Some(account) => WriteOp::PutAccount(*pubkey, account.clone()),
+            None => WriteOp::DeleteAccount(*pubkey),
+        });
+    }
+
+    pub fn put_entry(&mut self, seq: u64, entry: LedgerEntry) {
+        self.ops.push(WriteOp::PutEntry(seq, entry));
+    }
+
+    pub fn delete_entries(&mut self, seqs: Range<u64>) {
+        self.ops.push(WriteOp::DeleteEntries(seqs));
+    }
+}
+
+/// Persistence behind [`crate::ledger::Ledger`]: blocks by slot, accounts by pubkey and the
+/// replay log of [`LedgerEntry`] records by sequence number.
+pub trait LedgerStore: Send + Sync {
+    fn get_block(&self, slot: u64) -> Result<Option<Block>, LedgerError>;
+    /// Blocks with slots in `slots`, ascending.
+    fn blocks(&self, slots: Range<u64>) -> Result<Vec<Block>, LedgerError>;
+    #[allow(dead_code)]
+    fn get_account(&self, pubkey: &Pubkey) -> Result<Option<Account>, LedgerError>;
+    fn accounts(&self) -> Result<Vec<(Pubkey, Account)>, LedgerError>;
+    /// Entries with sequence numbers in `seqs`, ascending.
+    fn entries(&self, seqs: Range<u64>) -> Result<Vec<(u64, LedgerEntry)>, LedgerError>;
+    /// First and last entry sequence numbers, if the log is not empty.
+    fn entry_bounds(&self) -> Result<Option<(u64, u64)>, LedgerError>;
+    /// Applies every op in `batch`, in order, or none of them.
+    fn commit(&mut self, batch: WriteBatch) -> Result<(), LedgerError>;
+
+    #[allow(dead_code)]
+    fn put_block(&mut self, block: &Block) -> Result<(), LedgerError> {
+        let mut batch = WriteBatch::new();
+        batch.put_block(block);
+        self.commit(batch)
+    }
+
+    #[allow(dead_code)]
+    fn put_account(&mut self, pubkey: &Pubkey, account: Option<&Account>) -> Result<(), LedgerError> {
+        let mut batch = WriteBatch::new();
+        batch.put_account(pubkey, account);
+        self.commit(batch)
+    }
+}
+
+/// Everything in memory; nothing survives a restart.
+#[derive(Default)]
+pub struct MemoryStore {
+    blocks: BTreeMap<u64, Block>,
+    accounts: BTreeMap<Pubkey, Account>,
+    entries: BTreeMap<u64, LedgerEntry>,
+}
+
+impl MemoryStore {
+    pub fn new() -> Self {
+        MemoryStore::default()
+    }
+}
+
+impl LedgerStore for MemoryStore {
+    fn get_block(&self, slot: u64) -> Result<Option<Block>, LedgerError> {
+        Ok(self.blocks.get(&slot).cloned())
+    }
+
+    fn blocks(&self, slots: Range<u64>) -> Result<Vec<Block>, LedgerError> {
+        Ok(self.blocks.range(slots).map(|(_, block)| block.clone()).collect())
+    }
+
+    fn get_account(&self, pubkey: &Pubkey) -> Result<Option<Account>, LedgerError> {
+        Ok(self.accounts.get(pubkey).cloned())
+    }
+
+    fn accounts(&self) -> Result<Vec<(Pubkey, Account)>, LedgerError> {
+        Ok(self.accounts.iter().map(|(pubkey, account)| (*pubkey, account.clone())).collect())
+    }
+
+    fn entries(&self, seqs: Range<u64>) -> Result<Vec<(u64, LedgerEntry)>, LedgerError> {
+        Ok(self.entries.range(seqs).map(|(seq, entry)| (*seq, entry.clone())).collect())
+    }
+
+    fn entry_bounds(&self) -> Result<Option<(u64, u64)>, LedgerError> {
+        Ok(self.entries.keys().next().zip(self.entries.keys().next_back()).map(|(first, last)| (*first, *last)))
+    }
+
+    fn commit(&mut self, batch: WriteBatch) -> Result<(), LedgerError> {
+        for op in batch.ops {
+            match op {
+                WriteOp::PutBlock(block) => {
+                    self.blocks.insert(block.slot, block);
+                }
+                WriteOp::DeleteBlocks(slots) => self.blocks.retain(|slot, _| !slots.contains(slot)),
+                WriteOp::PutAccount(pubkey, account) => {
+                    self.accounts.insert(pubkey, account);
+                }
+                WriteOp::DeleteAccount(pubkey) => {
+                    self.accounts.remove(&pubkey);
+                }
+                WriteOp::PutEntry(seq, entry) => {
+                    self.entries.insert(seq, entry);
+                }
+                WriteOp::DeleteEntries(seqs) => self.entries.retain(|seq, _| !seqs.contains(seq)),
+            }
+        }
+        Ok(())
+    }
+}
+
+const BLOCKS: TableDefinition<u64, &[u8]> = TableDefinition::new("blocks");
+const ACCOUNTS: TableDefinition<&[u8; 32], &[u8]> = TableDefinition::new("accounts");
+const ENTRIES: TableDefinition<u64, &[u8]> = TableDefinition::new("entries");
+
+fn storage_error(e: impl std::fmt::Display) -> LedgerError {
+    LedgerError::Storage(e.to_string())
+}
+
+fn encode<T: serde::Serialize>(value: &T) -> Result<Vec<u8>, LedgerError> {
+    bincode::serialize(value).map_err(storage_error)
+}
+
+fn decode<T: serde::de::DeserializeOwned>(bytes: &[u8]) -> Result<T, LedgerError> {
+    bincode::deserialize(bytes).map_err(storage_error)
+}
+
+/// Embedded on-disk key-value store (redb): copy-on-write B-trees, so each batch commits
+/// atomically and survives a crash at any point.
+pub struct RedbStore {
+    db: Database,
+}
+
+impl RedbStore {
+    pub fn open(path: &str) -> Result<Self, LedgerError> {
+        let db = Database::create(path).map_err(storage_error)?;
+        // Create the tables up front so read transactions can always open them
+        let txn = db.begin_write().map_err(storage_error)?;
+        txn.open_table(BLOCKS).map_err(storage_error)?;
+        txn.open_table(ACCOUNTS).map_err(storage_error)?;
+        txn.open_table(ENTRIES).map_err(storage_error)?;
+        txn.commit().map_err(storage_error)?;
+        Ok(RedbStore { db })
+    }
+}
+
+impl LedgerStore for RedbStore {
+    fn get_block(&self, slot: u64) -> Result<Option<Block>, LedgerError> {
+        let txn = self.db.begin_read().map_err(storage_error)?;
+        let table = txn.open_table(BLOCKS).map_err(storage_error)?;
+        let value = table.get(slot).map_err(storage_error)?;
+        value.map(|bytes| decode(bytes.value())).transpose()
+    }
+
+    fn blocks(&self, slots: Range<u64>) -> Result<Vec<Block>, LedgerError> {
+        let txn = self.db.begin_read().map_err(storage_error)?;
+        let table = txn.open_table(BLOCKS).map_err(storage_error)?;
+        let mut blocks = Vec::new();
+        for item in table.range(slots).map_err(storage_error)? {
+            let (_, bytes) = item.map_err(storage_error)?;
+            blocks.push(decode(bytes.value())?);
+        }
+        Ok(blocks)
+    }
+
+    fn get_account(&self, pubkey: &Pubkey) -> Result<Option<Account>, LedgerError> {
+        let txn = self.db.begin_read().map_err(storage_error)?;
+        let table = txn.open_table(ACCOUNTS).map_err(storage_error)?;
+        let value = table.get(&pubkey.to_bytes()).map_err(storage_error)?;
+        value.map(|bytes| decode(bytes.value())).transpose()
+    }
+
+    fn accounts(&self) -> Result<Vec<(Pubkey, Account)>, LedgerError> {
+        let txn = self.db.begin_read().map_err(storage_error)?;
+        let table = txn.open_table(ACCOUNTS).map_err(storage_error)?;
+        let mut accounts = Vec::with_capacity(table.len().map_err(storage_error)? as usize);
+        for item in table.iter().map_err(storage_error)? {
+            let (key, bytes) = item.map_err(storage_error)?;
+            accounts.push((Pubkey::new_from_array(*key.value()), decode(bytes.value())?));
+        }
+        Ok(accounts)
+    }
+
+    fn entries(&self, seqs: Range<u64>) -> Result<Vec<(u64, LedgerEntry)>, LedgerError> {
+        let txn = self.db.begin_read().map_err(storage_error)?;
+        let table = txn.open_table(ENTRIES).map_err(storage_error)?;
+        let mut entries = Vec::new();
+        for item in table.range(seqs).map_err(storage_error)? {
+            let (seq, bytes) = item.map_err(storage_error)?;
+            entries.push((seq.value(), decode(bytes.value())?));
+        }
+        Ok(entries)
+    }
+
+    fn entry_bounds(&self) -> Result<Option<(u64, u64)>, LedgerError> {
+        let txn = self.db.begin_read().map_err(storage_error)?;
+        let table = txn.open_table(ENTRIES).map_err(storage_error)?;
+        let first = table.first().map_err(storage_error)?.map(|(seq, _)| seq.value());
+        let last = table.last().map_err(storage_error)?.map(|(seq, _)| seq.value());
+        Ok(first.zip(last))
+    }
+
+    fn commit(&mut self, batch: WriteBatch) -> Result<(), LedgerError> {
+        let txn = self.db.begin_write().map_err(storage_error)?;
+        {
+            let mut blocks = txn.open_table(BLOCKS).map_err(storage_error)?;
+            let mut accounts = txn.open_table(ACCOUNTS).map_err(storage_error)?;
+            let mut entries = txn.open_table(ENTRIES).map_err(storage_error)?;
+            for op in batch.ops {
+                match op {
+                    WriteOp::PutBlock(block) => {
+                        blocks.insert(block.slot, encode(&block)?.as_slice()).map_err(storage_error)?;
+                    }
+                    WriteOp::DeleteBlocks(slots) => {
+                        blocks.retain_in(slots, |_, _| false).map_err(storage_error)?;
+                    }
+                    WriteOp::PutAccount(pubkey, account) => {
+                        accounts.insert(&pubkey.to_bytes(), encode(&account)?.as_slice()).map_err(storage_error)?;
+                    }
+                    WriteOp::DeleteAccount(pubkey) => {
+                        accounts.remove(&pubkey.to_bytes()).map_err(storage_error)?;
+                    }
+                    WriteOp::PutEntry(seq, entry) => {
+                        entries.insert(seq, encode(&entry)?.as_slice()).map_err(storage_error)?;
+                    }
+                    WriteOp::DeleteEntries(seqs) => {
+                        entries.retain_in(seqs, |_, _| false).map_err(storage_error)?;
+                    }
+                }
+            }
+        }
+        // Dropping the transaction on any error above aborts the whole batch
+        txn.commit().map_err(storage_error)
+    }
+}
+
+/// On-disk store file for a ledger path.
+pub fn store_path(ledger_path: &str) -> String {
+    format!("{}.db", ledger_path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicU32, Ordering};
+    use rand::{Rng, SeedableRng, rngs::StdRng};
+    use solana_sdk::{hash::Hash, signature::{Keypair, Signer}};
+    use crate::ledger::{test_support, Ledger, Retention};
+
+    /// Wraps a store and fails a share of commits before they reach it, as a full disk or a
+    /// crashed write would. Reads always pass through.
+    struct FaultyStore {
+        inner: Box<dyn LedgerStore>,
+        // Percentage of commits to fail, shared so the test can change it while the ledger owns the store
+        fail_percent: Arc<AtomicU32>,
+        rng: StdRng,
+    }
+
+    impl FaultyStore {
+        fn new(inner: Box<dyn LedgerStore>, seed: u64) -> Self {
+            FaultyStore { inner, fail_percent: Arc::new(AtomicU32::new(0)), rng: StdRng::seed_from_u64(seed) }
+        }
+    }
+
+    impl LedgerStore for FaultyStore {
+        fn get_block(&self, slot: u64) -> Result<Option<Block>, LedgerError> {
+            self.inner.get_block(slot)
+        }
+
+        fn blocks(&self, slots: Range<u64>) -> Result<Vec<Block>, LedgerError> {
+            self.inner.blocks(slots)
+        }
+
+        fn get_account(&self, pubkey: &Pubkey) -> Result<Option<Account>, LedgerError> {
+            self.inner.get_account(pubkey)
+        }
+
+        fn accounts(&self) -> Result<Vec<(Pubkey, Account)>, LedgerError> {
+            self.inner.accounts()
+        }
+
+        fn entries(&self, seqs: Range<u64>) -> Result<Vec<(u64, LedgerEntry)>, LedgerError> {
+            self.inner.entries(seqs)
+        }
+
+        fn entry_bounds(&self) -> Result<Option<(u64, u64)>, LedgerError> {
+            self.inner.entry_bounds()
+        }
+
+        fn commit(&mut self, batch: WriteBatch) -> Result<(), LedgerError> {
+            if self.rng.gen_range(0..100) < self.fail_percent.load(Ordering::Relaxed) {
+                return Err(LedgerError::Storage("injected commit failure".to_string()));
+            }
+            self.inner.commit(batch)
+        }
+    }
+
+    /// What a failed operation must leave untouched: every account, the fee totals and the head.
+    fn fingerprint(ledger: &Ledger) -> (HashMap<Pubkey, Account>, u64, u64, Option<u64>) {
+        (
+            ledger.get_accounts().clone(),
+            ledger.fees_burned(),
+            ledger.fees_collected(),
+            ledger.get_last_block().map(|b| b.slot),
+        )
+    }
+
+    /// Applies the same batches, overwrites and deletes included, to both backends and reads
+    /// everything back.
+    #[test]
+    fn memory_and_redb_stores_agree() {
+        let dir = test_support::temp_dir("parity");
+        let mut stores: Vec<Box<dyn LedgerStore>> = vec![
+            Box::new(MemoryStore::new()),
+            Box::new(RedbStore::open(&store_path(&test_support::ledger_path(&dir))).unwrap()),
+        ];
+        let leader = Pubkey::new_unique();
+        let pubkeys: Vec<Pubkey> = (0..3).map(|_| Pubkey::new_unique()).collect();
+        let mut first = WriteBatch::new();
+        for slot in 1..=5 {
+            first.put_block(&test_support::unsigned_block(slot, &leader, Vec::new()));
+        }
+        for (n, pubkey) in pubkeys.iter().enumerate() {
+            first.put_account(pubkey, Some(&Account::new_system(n as u64 + 1)));
+        }
+        for seq in 0..10 {
+            first.put_entry(seq, LedgerEntry::Blockhash(Hash::new_unique()));
+        }
+        let mut second = WriteBatch::new();
+        second.delete_blocks(2..4);
+        second.put_account(&pubkeys[0], Some(&Account::new_system(100)));
+        second.put_account(&pubkeys[1], None);
+        second.delete_entries(0..4);
+
+        for store in &mut stores {
+            store.commit(first.clone()).unwrap();
+            store.commit(second.clone()).unwrap();
+        }
+        let read = |store: &dyn LedgerStore| {
+            (
+                encode(&store.blocks(0..10).unwrap()).unwrap(),
+                encode(&store.get_block(4).unwrap()).unwrap(),
+                store.accounts().unwrap(),
+                pubkeys.iter().map(|pubkey| store.get_account(pubkey).unwrap()).collect::<Vec<_>>(),
+                encode(&store.entries(0..20).unwrap()).unwrap(),
+                store.entry_bounds().unwrap(),
+            )
+        };
+        let (memory, redb) = (read(stores[0].as_ref()), read(stores[1].as_ref()));
+        drop(stores);
+        let _ = std::fs::remove_dir_all(&dir);
+        assert!(memory == redb, "memory and redb stores read back different state");
+        let (_, _, accounts, _, _, bounds) = memory;
+        assert_eq!(accounts.len(), 2);
+        assert_eq!(bounds, Some((4, 9)));
+    }
+
+    #[test]
+    fn unusable_store_fails_open() {
+        let dir = test_support::temp_dir("unusable-store");
+        let path = test_support::ledger_path(&dir);
+        // A directory where the database file should be
+        std::fs::create_dir_all(store_path(&path)).unwrap();
+        let opened = Ledger::open(path, Retention::default());
+        let _ = std::fs::remove_dir_all(&dir);
+        assert!(matches!(opened, Err(LedgerError::Storage(_))));
+    }
+
+    /// Drives airdrops, direct transactions and blocks through a ledger whose on-disk store fails
+    /// 30% of commits. After every operation memory must match the store, and a failed operation
+    /// must leave memory untouched. Finally the ledger is reopened from disk and compared.
+    #[test]
+    fn failed_commits_leave_ledger_unchanged() {
+        const ROUNDS: usize = 60;
+        const FAIL_PERCENT: u32 = 30;
+        const SENDERS: usize = 8;
+        const TXS_PER_BLOCK: usize = 4;
+        let dir = test_support::temp_dir("faults");
+        let path = test_support::ledger_path(&dir);
+        let store = FaultyStore::new(Box::new(RedbStore::open(&store_path(&path)).unwrap()), 7);
+        let faults = store.fail_percent.clone();
+        let mut ledger = Ledger::with_store(path.clone(), Retention::default(), Box::new(store)).unwrap();
+        let leader = Keypair::new();
+        let senders: Vec<Keypair> = (0..SENDERS).map(|_| Keypair::new()).collect();
+        for sender in &senders {
+            ledger.airdrop(&sender.pubkey().to_string(), 100 * 1_000_000_000).unwrap();
+        }
+        faults.store(FAIL_PERCENT, Ordering::Relaxed);
+
+        let (mut applied, mut rolled_back) = (0, 0);
+        let transfer = |sender: &Keypair, blockhash: Hash| test_support::transfer(sender, &Pubkey::new_unique(), 1_000_000, blockhash);
+        for round in 0..ROUNDS {
+            let slot = ledger.get_last_block().map(|b| b.slot).unwrap_or(0) + 1;
+            let blockhash = ledger.latest_blockhash();
+            for op in 0..3 {
+                let before = fingerprint(&ledger);
+                let sender = &senders[(round * 3 + op) % SENDERS];
+                let result = match op {
+                    0 => ledger.airdrop(&sender.pubkey().to_string(), 1_000_000_000),
+                    1 => ledger.add_transaction(transfer(sender, blockhash), slot),
+                    _ => {
+                        let transactions = senders.iter().take(TXS_PER_BLOCK).map(|s| transfer(s, blockhash)).collect();
+                        let block = test_support::unsigned_block(slot, &leader.pubkey(), transactions);
+                        ledger.produce_block(block, &leader).map(|_| ())
+                    }
+                };
+                match result {
+                    Ok(()) => applied += 1,
+                    Err(LedgerError::Storage(_)) => {
+                        rolled_back += 1;
+                        assert!(fingerprint(&ledger) == before, "round {}: failed op {} changed in-memory state", round, op);
+                    }
+                    Err(e) => panic!("round {}: op {} failed: {}", round, op, e),
+                }
+                let divergence = ledger.store_divergence().unwrap();
+                assert!(divergence.is_empty(), "round {}: {} accounts differ between memory and store", round, divergence.len());
+            }
+        }
+        assert!(applied > 0 && rolled_back > 0, "{} applied, {} rolled back", applied, rolled_back);
+
+        let (accounts, _, _, head) = fingerprint(&ledger);
+        drop(ledger);
+        let reopened = Ledger::open(path, Retention::default()).unwrap();
+        let (reopened_accounts, _, _, reopened_head) = fingerprint(&reopened);
+        drop(reopened);
+        let _ = std::fs::remove_dir_all(&dir);
+        assert_eq!(reopened_head, head);
+        assert!(reopened_accounts == accounts, "reopened ledger has {} accounts, expected {}", reopened_accounts.len(), accounts.len());
+    }
+}
@@ -0,0 +1,132 @@
+// Patent Pending Copyright © 2025 Xeris Web Co. All rights reserved.
+// XerisCoin System Program - Transfer, CreateAccount, Assign, Allocate (Local Alpha)
+// Decodes bincode SystemInstruction data against the transaction's loaded accounts
+
+use solana_sdk::{instruction::CompiledInstruction, message::Message, pubkey::Pubkey};
+#[allow(deprecated)]
+use solana_sdk::{system_instruction::SystemInstruction, system_program};
+use crate::accounts::Account;
+use crate::error::LedgerError;
+
+/// Largest data allocation the system program will make for a single account.
+pub const MAX_PERMITTED_DATA_LENGTH: u64 = 10 * 1024 * 1024;
+
+/// Resolves the `position`-th account of an instruction to its index in the message.
+pub fn instruction_account(message: &Message, ix: &CompiledInstruction, position: usize) -> Result<usize, LedgerError> {
+    ix.accounts
+        .get(position)
+        .map(|&index| index as usize)
+        .filter(|&index| index < message.account_keys.len())
+        .ok_or_else(|| LedgerError::InvalidInstruction(format!("missing instruction account {}", position)))
+}
+
+fn require_signer(message: &Message, index: usize) -> Result<(), LedgerError> {
+    if message.is_signer(index) {
+        Ok(())
+    } else {
+        Err(LedgerError::InvalidInstruction(format!("{} must sign", message.account_keys[index])))
+    }
+}
+
+fn require_writable(message: &Message, index: usize) -> Result<(), LedgerError> {
+    if message.is_maybe_writable(index, None) {
+        Ok(())
+    } else {
+        Err(LedgerError::InvalidInstruction(format!("{} must be writable", message.account_keys[index])))
+    }
+}
+
+fn require_system_owned(message: &Message, index: usize, account: &Account) -> Result<(), LedgerError> {
+    if account.owner == system_program::id() && account.data.is_empty() {
+        Ok(())
+    } else {
+        Err(LedgerError::InvalidInstruction(format!(
+            "{} is not a system account",
+            message.account_keys[index]
+        )))
+    }
+}
+
+fn allocate(account: &mut Account, space: u64) -> Result<(), LedgerError> {
+    if space > MAX_PERMITTED_DATA_LENGTH {
+        return Err(LedgerError::InvalidInstruction(format!(
+            "space {} exceeds {} bytes",
+            space, MAX_PERMITTED_DATA_LENGTH
+        )));
+    }
+    account.data = vec![0u8; space as usize];
+    Ok(())
+}
+
+fn transfer(message: &Message, accounts: &mut [Account], from: usize, to: usize, lamports: u64) -> Result<(), LedgerError> {
+    require_signer(message, from)?;
+    require_writable(message, from)?;
+    require_writable(message, to)?;
+    require_system_owned(message, from, &accounts[from])?;
+    let balance = accounts[from].lamports;
+    if balance < lamports {
+        return Err(LedgerError::InsufficientFunds {
+            account: message.account_keys[from].to_string(),
+            balance,
+            required: lamports,
+        });
+    }
+    accounts[from].lamports -= lamports;
+    accounts[to].lamports = accounts[to]
+        .lamports
+        .checked_add(lamports)
+        .ok_or_else(|| LedgerError::InvalidInstruction("lamports overflow".to_string()))?;
+    Ok(())
+}
+
+/// Applies one system program instruction to `accounts` (aligned with `message.account_keys`).
+pub fn process_instruction(message: &Message, ix: &CompiledInstruction, accounts: &mut [Account]) -> Result<(), LedgerError> {
+    let instruction: SystemInstruction = bincode::deserialize(&ix.data)
+        .map_err(|e| LedgerError::InvalidInstruction(format!("invalid system instruction: {}", e)))?;
+    match instruction {
+        SystemInstruction::Transfer { lamports } => {
+            let from = instruction_account(message, ix, 0)?;
+            let to = instruction_account(message, ix, 1)?;
+            transfer(message, accounts, from, to, lamports)
+        }
+        SystemInstruction::CreateAccount { lamports, space, owner } => {
+            let from = instruction_account(message, ix, 0)?;
+            let to = instruction_account(message, ix, 1)?;
+            require_signer(message, to)?;
+            if !accounts[to].is_empty() {
+                return Err(LedgerError::InvalidInstruction(format!(
+                    "account {} already in use",
+                    message.account_keys[to]
+                )));
+            }
+            allocate(&mut accounts[to], space)?;
+            accounts[to].owner = owner;
+            transfer(message, accounts, from, to, lamports)
+        }
+        SystemInstruction::Assign { owner } => {
+            let index = instruction_account(message, ix, 0)?;
+            require_signer(message, index)?;
+            require_writable(message, index)?;
+            if accounts[index].owner != system_program::id() {
+                return Err(LedgerError::InvalidInstruction(format!(
+                    "{} is not owned by the system program",
+                    message.account_keys[index]
+                )));
+            }
+            accounts[index].owner = owner;
+            Ok(())
+        }
+        SystemInstruction::Allocate { space } => {
+            let index = instruction_account(message, ix, 0)?;
+            require_signer(message, index)?;
+            require_writable(message, index)?;
+            require_system_owned(message, index, &accounts[index])?;
+            allocate(&mut accounts[index], space)
+        }
+        other => Err(LedgerError::InvalidInstruction(format!("unsupported system instruction: {:?}", other))),
+    }
+}
+
+pub fn is_system_program(program_id: &Pubkey) -> bool {
+    *program_id == system_program::id()
+}
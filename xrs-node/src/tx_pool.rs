@@ -1,54 +1,307 @@
 // Patent Pending Copyright © 2025 Xeris Web Co. All rights reserved.
-// Priority TX Queue - Fee-Based (Local Alpha)
-use solana_sdk::transaction::Transaction;
-use std::cmp::Ordering;
+// Mempool - Fee-Prioritized, Per-Sender Ordered, Bounded (Local Alpha)
+// Dedupe by signature, evict lowest fee when full, expire aged-out blockhashes
+use solana_sdk::{hash::Hash, pubkey::Pubkey, signature::Signature, transaction::Transaction};
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, BTreeSet, BinaryHeap, HashMap};
+use serde::Serialize;
+use crate::error::MempoolError;
+
+pub const MAX_MEMPOOL_SIZE: usize = 10_000;
+pub const MAX_TXS_PER_SENDER: usize = 64;
+pub const MAX_TXS_PER_BLOCK: usize = 2_048;
 
-#[allow(dead_code)]
 #[derive(Clone, PartialEq, Eq)]
 pub struct PrioritizedTx {
     pub tx: Transaction,
     pub fee: u64,
+    pub sender: Pubkey,
+    pub blockhash: Hash,
+    seq: u64,
 }
 
-impl Ord for PrioritizedTx {
-    fn cmp(&self, other: &Self) -> Ordering {
-        other.fee.cmp(&self.fee)
+impl PrioritizedTx {
+    pub fn signature(&self) -> Signature {
+        self.tx.signatures[0]
     }
 }
 
-impl PartialOrd for PrioritizedTx {
-    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        Some(self.cmp(other))
-    }
+#[derive(Serialize, Clone, Debug, Default)]
+pub struct MempoolStats {
+    pub size: usize,
+    pub senders: usize,
+    pub min_fee: u64,
+    pub p50_fee: u64,
+    pub p90_fee: u64,
+    pub p99_fee: u64,
+    pub max_fee: u64,
+    pub inserted: u64,
+    pub evicted: u64,
+    pub expired: u64,
 }
 
-#[allow(dead_code)]
-pub struct PriorityQueue {
-    inner: std::collections::BinaryHeap<PrioritizedTx>,
+/// Priority key: highest fee first, then earliest arrival.
+type PriorityKey = (u64, Reverse<u64>, Signature);
+
+pub struct Mempool {
+    max_size: usize,
+    max_per_sender: usize,
+    next_seq: u64,
+    by_signature: HashMap<Signature, PrioritizedTx>,
+    by_priority: BTreeSet<PriorityKey>,
+    // Per-sender arrival order: a sender's transactions leave the pool in the order they were submitted
+    by_sender: HashMap<Pubkey, BTreeMap<u64, Signature>>,
+    inserted: u64,
+    evicted: u64,
+    expired: u64,
 }
 
-impl PriorityQueue {
+impl Mempool {
     pub fn new() -> Self {
-        PriorityQueue {
-            inner: std::collections::BinaryHeap::new(),
+        Self::with_limits(MAX_MEMPOOL_SIZE, MAX_TXS_PER_SENDER)
+    }
+
+    pub fn with_limits(max_size: usize, max_per_sender: usize) -> Self {
+        Mempool {
+            max_size,
+            max_per_sender,
+            next_seq: 0,
+            by_signature: HashMap::new(),
+            by_priority: BTreeSet::new(),
+            by_sender: HashMap::new(),
+            inserted: 0,
+            evicted: 0,
+            expired: 0,
         }
     }
 
-    pub fn push(&mut self, tx: PrioritizedTx) {
-        self.inner.push(tx);
+    /// Inserts a transaction, returning the signature evicted to make room (if any).
+    /// A full pool (or a sender at its limit) only admits a transaction paying more than the lowest fee it would replace.
+    pub fn insert(&mut self, tx: Transaction, fee: u64) -> Result<Option<Signature>, MempoolError> {
+        let signature = *tx.signatures.first().ok_or(MempoolError::MissingSignature)?;
+        if self.by_signature.contains_key(&signature) {
+            return Err(MempoolError::Duplicate(signature.to_string()));
+        }
+        let sender = *tx.message.account_keys.first().ok_or(MempoolError::MissingSignature)?;
+        let mut evicted = None;
+        let sender_count = self.by_sender.get(&sender).map(|q| q.len()).unwrap_or(0);
+        if sender_count >= self.max_per_sender {
+            let lowest = self.lowest_fee_for_sender(&sender);
+            match lowest {
+                Some((lowest_fee, lowest_sig)) if fee > lowest_fee => {
+                    self.remove(&lowest_sig);
+                    evicted = Some(lowest_sig);
+                }
+                _ => return Err(MempoolError::SenderLimit { sender: sender.to_string(), limit: self.max_per_sender }),
+            }
+        } else if self.by_signature.len() >= self.max_size {
+            match self.by_priority.first().copied() {
+                Some((lowest_fee, _, lowest_sig)) if fee > lowest_fee => {
+                    self.remove(&lowest_sig);
+                    evicted = Some(lowest_sig);
+                }
+                Some((lowest_fee, _, _)) => return Err(MempoolError::Full { min_fee: lowest_fee }),
+                None => return Err(MempoolError::Full { min_fee: 0 }),
+            }
+        }
+        if evicted.is_some() {
+            self.evicted += 1;
+        }
+        let seq = self.next_seq;
+        self.next_seq += 1;
+        let entry = PrioritizedTx {
+            blockhash: tx.message.recent_blockhash,
+            tx,
+            fee,
+            sender,
+            seq,
+        };
+        self.by_priority.insert((fee, Reverse(seq), signature));
+        self.by_sender.entry(sender).or_default().insert(seq, signature);
+        self.by_signature.insert(signature, entry);
+        self.inserted += 1;
+        Ok(evicted)
     }
 
-    pub fn len(&self) -> usize {
-        self.inner.len()
+    fn lowest_fee_for_sender(&self, sender: &Pubkey) -> Option<(u64, Signature)> {
+        self.by_sender
+            .get(sender)?
+            .values()
+            .filter_map(|sig| self.by_signature.get(sig).map(|e| (e.fee, *sig)))
+            .min_by_key(|(fee, _)| *fee)
     }
 
-    #[allow(dead_code)]
-    pub fn retain<F>(&mut self, f: F) where F: FnMut(&PrioritizedTx) -> bool {
-        self.inner.retain(f);
+    pub fn remove(&mut self, signature: &Signature) -> Option<PrioritizedTx> {
+        let entry = self.by_signature.remove(signature)?;
+        self.by_priority.remove(&(entry.fee, Reverse(entry.seq), *signature));
+        if let Some(queue) = self.by_sender.get_mut(&entry.sender) {
+            queue.remove(&entry.seq);
+            if queue.is_empty() {
+                self.by_sender.remove(&entry.sender);
+            }
+        }
+        Some(entry)
     }
 
     #[allow(dead_code)]
-    pub fn drain(&mut self) -> std::collections::binary_heap::Drain<'_, PrioritizedTx> {
-        self.inner.drain()
+    pub fn contains(&self, signature: &Signature) -> bool {
+        self.by_signature.contains_key(signature)
+    }
+
+    /// Drops every transaction whose recent blockhash is no longer valid; returns how many expired.
+    pub fn expire<F>(&mut self, is_blockhash_valid: F) -> usize where F: Fn(&Hash) -> bool {
+        let stale: Vec<Signature> = self
+            .by_signature
+            .values()
+            .filter(|e| !is_blockhash_valid(&e.blockhash))
+            .map(|e| e.signature())
+            .collect();
+        for signature in &stale {
+            self.remove(signature);
+        }
+        self.expired += stale.len() as u64;
+        stale.len()
+    }
+
+    /// Removes up to `max` transactions for a block: highest fee first across senders,
+    /// but each sender's transactions strictly in submission order.
+    pub fn take_batch(&mut self, max: usize) -> Vec<PrioritizedTx> {
+        let mut heads: BinaryHeap<(u64, Reverse<u64>, Pubkey)> = BinaryHeap::new();
+        for queue in self.by_sender.values() {
+            if let Some((_, sig)) = queue.iter().next() {
+                let entry = &self.by_signature[sig];
+                heads.push((entry.fee, Reverse(entry.seq), entry.sender));
+            }
+        }
+        let mut batch = Vec::new();
+        while batch.len() < max {
+            let Some((_, Reverse(seq), sender)) = heads.pop() else { break };
+            let signature = self.by_sender[&sender][&seq];
+            if let Some(entry) = self.remove(&signature) {
+                batch.push(entry);
+            }
+            if let Some((_, next_sig)) = self.by_sender.get(&sender).and_then(|q| q.iter().next()) {
+                let next = &self.by_signature[next_sig];
+                heads.push((next.fee, Reverse(next.seq), next.sender));
+            }
+        }
+        batch
+    }
+
+    pub fn stats(&self) -> MempoolStats {
+        // by_priority iterates in ascending fee order
+        let fees: Vec<u64> = self.by_priority.iter().map(|(fee, _, _)| *fee).collect();
+        let percentile = |p: usize| -> u64 {
+            if fees.is_empty() {
+                0
+            } else {
+                fees[((fees.len() - 1) * p) / 100]
+            }
+        };
+        MempoolStats {
+            size: fees.len(),
+            senders: self.by_sender.len(),
+            min_fee: fees.first().copied().unwrap_or(0),
+            p50_fee: percentile(50),
+            p90_fee: percentile(90),
+            p99_fee: percentile(99),
+            max_fee: fees.last().copied().unwrap_or(0),
+            inserted: self.inserted,
+            evicted: self.evicted,
+            expired: self.expired,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_sdk::signature::Keypair;
+    use crate::ledger::test_support;
+
+    /// A transaction from `sender`; `lamports` only makes its signature unique.
+    fn tx(sender: &Keypair, lamports: u64) -> Transaction {
+        test_support::transfer(sender, &Pubkey::new_unique(), lamports, Hash::default())
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn duplicate_signature_is_refused() {
+        let mut pool = Mempool::new();
+        let tx = tx(&Keypair::new(), 1);
+        pool.insert(tx.clone(), 10).unwrap();
+        assert!(matches!(pool.insert(tx, 20), Err(MempoolError::Duplicate(_))));
+        assert_eq!(pool.stats().size, 1);
+    }
+
+    #[test]
+    fn sender_at_limit_replaces_its_lowest_fee() {
+        let mut pool = Mempool::with_limits(100, 2);
+        let sender = Keypair::new();
+        let (cheap, dear) = (tx(&sender, 1), tx(&sender, 2));
+        pool.insert(cheap.clone(), 10).unwrap();
+        pool.insert(dear.clone(), 20).unwrap();
+        assert!(matches!(pool.insert(tx(&sender, 3), 10), Err(MempoolError::SenderLimit { limit: 2, .. })));
+        assert_eq!(pool.insert(tx(&sender, 4), 15).unwrap(), Some(cheap.signatures[0]));
+        assert!(pool.contains(&dear.signatures[0]));
+        // Other senders are unaffected by the limit
+        assert_eq!(pool.insert(tx(&Keypair::new(), 5), 1).unwrap(), None);
+        assert_eq!(pool.stats().evicted, 1);
+    }
+
+    #[test]
+    fn full_pool_evicts_the_lowest_fee() {
+        let mut pool = Mempool::with_limits(3, 10);
+        let lowest = tx(&Keypair::new(), 1);
+        pool.insert(tx(&Keypair::new(), 2), 5).unwrap();
+        pool.insert(lowest.clone(), 1).unwrap();
+        pool.insert(tx(&Keypair::new(), 3), 3).unwrap();
+        assert!(matches!(pool.insert(tx(&Keypair::new(), 4), 1), Err(MempoolError::Full { min_fee: 1 })));
+        assert_eq!(pool.insert(tx(&Keypair::new(), 5), 2).unwrap(), Some(lowest.signatures[0]));
+        assert_eq!(pool.stats().size, 3);
+        assert_eq!(pool.stats().min_fee, 2);
+    }
+
+    #[test]
+    fn expire_drops_transactions_with_stale_blockhashes() {
+        let mut pool = Mempool::new();
+        let sender = Keypair::new();
+        let (recent, stale) = (Hash::new_unique(), Hash::new_unique());
+        let fresh = test_support::transfer(&sender, &Pubkey::new_unique(), 1, recent);
+        pool.insert(fresh.clone(), 1).unwrap();
+        pool.insert(test_support::transfer(&sender, &Pubkey::new_unique(), 2, stale), 1).unwrap();
+        assert_eq!(pool.expire(|blockhash| *blockhash == recent), 1);
+        assert!(pool.contains(&fresh.signatures[0]));
+        assert_eq!((pool.stats().size, pool.stats().expired), (1, 1));
+    }
+
+    #[test]
+    fn take_batch_keeps_each_senders_order() {
+        let mut pool = Mempool::new();
+        let (a, b) = (Keypair::new(), Keypair::new());
+        let (a_first, a_second, b_only) = (tx(&a, 1), tx(&a, 2), tx(&b, 3));
+        pool.insert(a_first.clone(), 1).unwrap();
+        pool.insert(a_second.clone(), 100).unwrap();
+        pool.insert(b_only.clone(), 50).unwrap();
+        // A's high-fee transaction waits behind A's earlier, cheaper one
+        let batch: Vec<Signature> = pool.take_batch(2).iter().map(|entry| entry.signature()).collect();
+        assert_eq!(batch, vec![b_only.signatures[0], a_first.signatures[0]]);
+        let rest: Vec<Signature> = pool.take_batch(10).iter().map(|entry| entry.signature()).collect();
+        assert_eq!(rest, vec![a_second.signatures[0]]);
+        assert_eq!(pool.stats().size, 0);
+    }
+
+    #[test]
+    fn stats_report_fee_percentiles() {
+        let mut pool = Mempool::new();
+        assert_eq!((pool.stats().min_fee, pool.stats().p50_fee, pool.stats().max_fee), (0, 0, 0));
+        for fee in 1..=100 {
+            pool.insert(tx(&Keypair::new(), fee), fee).unwrap();
+        }
+        let stats = pool.stats();
+        assert_eq!(
+            (stats.min_fee, stats.p50_fee, stats.p90_fee, stats.p99_fee, stats.max_fee),
+            (1, 50, 90, 99, 100)
+        );
+        assert_eq!((stats.size, stats.senders, stats.inserted), (100, 100, 100));
+    }
+}
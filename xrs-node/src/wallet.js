@@ -19,7 +19,7 @@ async function sendXRS(fromKeypairPath, toPubkeyStr, amount) {
     const from = Keypair.fromSecretKey(Uint8Array.from(JSON.parse(fs.readFileSync(fromKeypairPath, 'utf8'))));
     const balance = await connection.getBalance(from.publicKey);  // Local balance
     const lamports = amount * 1e9;
-    const fee = lamports * 0.001; // 0.1% fee
+    const fee = 5000; // Base fee per signature (lamports); priority fees via ComputeBudget
     if (balance < lamports + fee) {
         throw new Error(`Insufficient local balance: ${balance / 1e9} XRS`);
     }
@@ -33,7 +33,7 @@ async function sendXRS(fromKeypairPath, toPubkeyStr, amount) {
     );
     const signature = await connection.sendTransaction(tx, [from]);
     await connection.confirmTransaction(signature);
-    console.log(`Local Send: ${amount} XRS to ${toPubkeyStr}. Fee: ${fee / 1e9} XRS. Sig: ${signature}`);
+    console.log(`Local Send: ${amount} XRS to ${toPubkeyStr}. Fee: ${fee} lamports. Sig: ${signature}`);
 }
 
 async function stakeXRS(fromKeypairPath, amount) {
@@ -61,8 +61,8 @@ async function stakeXRS(fromKeypairPath, amount) {
 async function claimAirdrop(address) {
     const response = await fetch(`http://127.0.0.1:4001/airdrop/${address}/1000000000000`, { method: 'POST' });
     const result = await response.json();
-    if (result.error) {
-        throw new Error(`Local Airdrop failed: ${result.error}`);
+    if (!result.ok) {
+        throw new Error(`Local Airdrop failed: ${result.error.code} - ${result.error.message}`);
     }
     console.log('Local Airdrop Claimed: 1,000 XRS');
 }